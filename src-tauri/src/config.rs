@@ -6,6 +6,8 @@ use crate::EasyKafkaResult;
 pub struct EasyKafkaConfig {
     pub database: DatabaseConfig,
     pub log: LogConfig,
+    #[serde(default)]
+    pub client: ClientCacheConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +29,18 @@ pub struct LogConfig {
     pub suffix: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientCacheConfig {
+    /// Kafka 客户端空闲多少秒后关闭
+    pub idle_ttl: u64,
+}
+
+impl Default for ClientCacheConfig {
+    fn default() -> Self {
+        Self { idle_ttl: 600 }
+    }
+}
+
 pub async fn read_config() -> EasyKafkaResult<EasyKafkaConfig> {
     let content = tokio::fs::read_to_string("config/config.toml").await?;
     let config: EasyKafkaConfig = toml::from_str(&content)?;
@@ -30,6 +30,62 @@ pub struct Model {
 
     #[serde(rename = "isActive")]
     pub connected: bool,
+
+    #[serde(rename = "schemaRegistryUrl", default)]
+    pub registry_url: Option<String>,
+
+    #[serde(rename = "schemaRegistryUsername", default)]
+    pub registry_username: Option<String>,
+
+    #[serde(rename = "schemaRegistryPassword", default)]
+    pub registry_password: Option<String>,
+
+    /// PLAINTEXT、SSL、SASL_PLAINTEXT 或 SASL_SSL，为空时根据 ssl 和 sasl 推断
+    #[serde(rename = "securityProtocol", default)]
+    pub security_protocol: Option<String>,
+
+    #[serde(rename = "sslCaLocation", default)]
+    pub ssl_ca_location: Option<String>,
+
+    #[serde(rename = "sslCertificateLocation", default)]
+    pub ssl_certificate_location: Option<String>,
+
+    #[serde(rename = "sslKeyLocation", default)]
+    pub ssl_key_location: Option<String>,
+
+    #[serde(rename = "sslKeyPassword", default)]
+    pub ssl_key_password: Option<String>,
+
+    /// 是否校验 broker 证书中的主机名
+    #[serde(rename = "sslVerifyHostname", default = "default_verify_hostname")]
+    pub ssl_verify_hostname: bool,
+
+    #[serde(rename = "kerberosKeytab", default)]
+    pub kerberos_keytab: Option<String>,
+
+    #[serde(rename = "kerberosPrincipal", default)]
+    pub kerberos_principal: Option<String>,
+
+    /// broker 的 Kerberos 服务名，为空时使用 kafka
+    #[serde(rename = "kerberosServiceName", default)]
+    pub kerberos_service_name: Option<String>,
+
+    /// OIDC client credentials 模式的 token 地址
+    #[serde(rename = "oauthTokenEndpoint", default)]
+    pub oauth_token_endpoint: Option<String>,
+
+    #[serde(rename = "oauthClientId", default)]
+    pub oauth_client_id: Option<String>,
+
+    #[serde(rename = "oauthClientSecret", default)]
+    pub oauth_client_secret: Option<String>,
+
+    #[serde(rename = "oauthScope", default)]
+    pub oauth_scope: Option<String>,
+}
+
+fn default_verify_hostname() -> bool {
+    true
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,4 +3,5 @@
 pub mod prelude;
 
 pub mod cluster;
+pub mod offset_backups;
 pub mod topics;
@@ -1,4 +1,5 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.16
 
 pub use super::cluster::Entity as Cluster;
+pub use super::offset_backups::Entity as OffsetBackups;
 pub use super::topics::Entity as Topics;
@@ -18,6 +18,22 @@ pub struct Model {
     pub replica: i32,
     #[sea_orm(column_type = "Text", nullable)]
     pub properties: Option<String>,
+
+    #[serde(rename = "clusterId", default)]
+    pub cluster_id: Option<String>,
+
+    #[serde(default)]
+    pub protected: bool,
+
+    /// key 格式，TopicFormat 中 key 的 JSON
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(rename = "keyFormat", default)]
+    pub key_format: Option<String>,
+
+    /// value 格式，TopicFormat 中 value 的 JSON
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(rename = "valueFormat", default)]
+    pub value_format: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
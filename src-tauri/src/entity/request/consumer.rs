@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumeMessagesRequest {
+    pub topic: String,
+
+    /// 读取的分区，为空时读取所有分区
+    pub partitions: Option<Vec<i32>>,
+
+    pub position: StartPosition,
+
+    /// 最多返回的消息数
+    pub limit: usize,
+
+    /// 过滤表达式，语法见 utils::message_filter
+    pub filter: Option<String>,
+}
+
+/// 分区的起始读取位置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StartPosition {
+    /// 从最早的消息开始
+    Earliest,
+    /// 读取每个分区最新的 limit 条消息
+    Latest,
+    /// 从指定 offset 开始
+    Offset { offset: i64 },
+    /// 从时间戳（毫秒）之后的第一条消息开始
+    Timestamp { timestamp: i64 },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchTopicRequest {
+    pub topic: String,
+
+    /// 过滤表达式，语法见 utils::message_filter
+    pub filter: String,
+
+    /// 搜索的分区，为空时搜索所有分区
+    pub partitions: Option<Vec<i32>>,
+
+    /// 时间窗口起点，毫秒，为空时从最早的消息开始
+    #[serde(rename = "startTimestamp")]
+    pub start_timestamp: Option<i64>,
+
+    /// 时间窗口终点，毫秒，为空时搜索到任务开始时的高水位
+    #[serde(rename = "endTimestamp")]
+    pub end_timestamp: Option<i64>,
+
+    /// 最多返回的匹配数，达到后结束搜索
+    #[serde(rename = "maxResults")]
+    pub max_results: Option<u64>,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResetOffsetsRequest {
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+
+    pub topic: String,
+
+    /// 重置的分区，为空时重置 topic 的所有分区
+    pub partitions: Option<Vec<i32>>,
+
+    pub position: ResetPosition,
+
+    /// 只计算重置后的位移，不提交
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+/// 重置到的位置，结果会限制在分区的水位范围内
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ResetPosition {
+    Earliest,
+    Latest,
+    Offset {
+        offset: i64,
+    },
+    /// 时间戳（毫秒）之后的第一条消息
+    Timestamp {
+        timestamp: i64,
+    },
+    /// 在已提交位移的基础上移动，负数向前，分区没有提交过位移时从最早的位置开始
+    Shift {
+        by: i64,
+    },
+    /// 从当前时间往前的毫秒数
+    Duration {
+        millis: i64,
+    },
+}
+
+/// 批量选择消费者组，指定的组和正则匹配到的组取并集
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupSelection {
+    #[serde(rename = "groupIds", default)]
+    pub group_ids: Vec<String>,
+
+    /// 需要完整匹配消费者组id
+    pub pattern: Option<String>,
+
+    /// 只选择没有成员的消费者组（Empty 或 Dead 状态）
+    #[serde(rename = "onlyEmpty", default)]
+    pub only_empty: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteGroupsRequest {
+    #[serde(flatten)]
+    pub selection: GroupSelection,
+
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteGroupOffsetsRequest {
+    #[serde(flatten)]
+    pub selection: GroupSelection,
+
+    pub topic: String,
+
+    /// 删除位移的分区，为空时删除 topic 所有分区的位移
+    pub partitions: Option<Vec<i32>>,
+
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
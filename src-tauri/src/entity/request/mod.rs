@@ -1 +1,4 @@
-
+pub mod consumer;
+pub mod consumer_group;
+pub mod producer;
+pub mod topic;
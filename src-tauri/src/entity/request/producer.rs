@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProduceMessageRequest {
+    pub topic: String,
+
+    pub key: Option<String>,
+
+    pub value: Option<String>,
+
+    #[serde(default)]
+    pub headers: Vec<MessageHeader>,
+
+    pub partition: Option<i32>,
+
+    /// 消息时间戳，毫秒
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageHeader {
+    pub key: String,
+
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchProduceRequest {
+    pub message: ProduceMessageRequest,
+
+    /// 发送的消息数
+    pub count: u64,
+
+    /// 发送间隔，毫秒
+    pub interval: Option<u64>,
+
+    /// 目标发送速率，条/秒，优先于 interval
+    pub rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileProduceRequest {
+    pub topic: String,
+
+    /// 本地文件路径
+    pub path: String,
+
+    pub format: RecordFileFormat,
+
+    #[serde(default)]
+    pub mapping: FieldMapping,
+
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+
+    /// dry-run 时校验的行数
+    #[serde(rename = "validateRows")]
+    pub validate_rows: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordFileFormat {
+    Jsonl,
+    Csv,
+}
+
+/// 文件字段到消息属性的映射，value 为空时整行作为消息内容
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FieldMapping {
+    pub key: Option<String>,
+
+    pub value: Option<String>,
+
+    #[serde(default)]
+    pub headers: Vec<String>,
+
+    pub partition: Option<String>,
+
+    pub timestamp: Option<String>,
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// 截断 topic 数据时删除到的位置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TruncatePosition {
+    /// 删除分区内的全部消息
+    HighWatermark,
+    /// 删除指定 offset 之前的消息
+    Offset { offset: i64 },
+    /// 删除指定时间戳（毫秒）之前的消息
+    Timestamp { timestamp: i64 },
+}
+
+/// 消息 key/value 的格式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+    #[default]
+    Text,
+    Json,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+    Avro,
+    Protobuf,
+    Hex,
+    Base64,
+    /// Confluent 格式：magic byte + 4 字节 schema id，schema 从 schema registry 获取
+    Registry,
+}
+
+/// 无法按格式解码时的展示方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct FormatConfig {
+    #[serde(default)]
+    pub format: MessageFormat,
+
+    /// avro 为 schema 文件，protobuf 为 .proto 文件或编译好的 descriptor set
+    #[serde(rename = "schemaPath", default)]
+    pub schema_path: Option<String>,
+
+    /// protobuf 消息的全名，如 com.example.Order
+    #[serde(rename = "messageType", default)]
+    pub message_type: Option<String>,
+
+    /// registry 格式发送消息时使用的 subject，为空时只能解码
+    #[serde(default)]
+    pub subject: Option<String>,
+
+    /// subject 的版本，为空时使用最新版本
+    #[serde(default)]
+    pub version: Option<i32>,
+
+    #[serde(default)]
+    pub fallback: BinaryEncoding,
+}
+
+/// topic 的 key 和 value 格式
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TopicFormat {
+    #[serde(default)]
+    pub key: FormatConfig,
+
+    #[serde(default)]
+    pub value: FormatConfig,
+}
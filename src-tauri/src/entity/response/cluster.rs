@@ -24,6 +24,9 @@ pub struct ClusterResponse {
     #[serde(rename = "name")]
     pub cluster_name: String,
 
+    #[serde(rename = "saslUsername")]
+    pub username: Option<String>,
+
     pub timeout: i32,
 
     #[serde(rename = "sslEnabled")]
@@ -34,4 +37,44 @@ pub struct ClusterResponse {
 
     #[serde(rename = "isActive")]
     pub connected: bool,
+
+    #[serde(rename = "schemaRegistryUrl")]
+    pub registry_url: Option<String>,
+
+    #[serde(rename = "schemaRegistryUsername")]
+    pub registry_username: Option<String>,
+
+    /// 实际使用的安全协议，旧数据根据 ssl 和 sasl 推断
+    #[serde(rename = "securityProtocol")]
+    pub security_protocol: Option<String>,
+
+    #[serde(rename = "sslCaLocation")]
+    pub ssl_ca_location: Option<String>,
+
+    #[serde(rename = "sslCertificateLocation")]
+    pub ssl_certificate_location: Option<String>,
+
+    #[serde(rename = "sslKeyLocation")]
+    pub ssl_key_location: Option<String>,
+
+    #[serde(rename = "sslVerifyHostname")]
+    pub ssl_verify_hostname: bool,
+
+    #[serde(rename = "kerberosKeytab")]
+    pub kerberos_keytab: Option<String>,
+
+    #[serde(rename = "kerberosPrincipal")]
+    pub kerberos_principal: Option<String>,
+
+    #[serde(rename = "kerberosServiceName")]
+    pub kerberos_service_name: Option<String>,
+
+    #[serde(rename = "oauthTokenEndpoint")]
+    pub oauth_token_endpoint: Option<String>,
+
+    #[serde(rename = "oauthClientId")]
+    pub oauth_client_id: Option<String>,
+
+    #[serde(rename = "oauthScope")]
+    pub oauth_scope: Option<String>,
 }
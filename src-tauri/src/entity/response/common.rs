@@ -23,4 +23,12 @@ impl<T> CommonResponse<T> {
             data: None,
         }
     }
+
+    pub fn error_with_data(msg: String, data: T) -> Self {
+        Self {
+            code: 400,
+            msg,
+            data: Some(data),
+        }
+    }
 }
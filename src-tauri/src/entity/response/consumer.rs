@@ -0,0 +1,102 @@
+use crate::entity::request::topic::MessageFormat;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConsumeMessagesResponse {
+    pub records: Vec<ConsumerRecord>,
+
+    /// 读取的消息数
+    pub scanned: u64,
+
+    /// 满足过滤条件的消息数
+    pub matched: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumerRecord {
+    pub topic: String,
+
+    pub partition: i32,
+
+    pub offset: i64,
+
+    pub key: Option<String>,
+
+    pub value: Option<String>,
+
+    /// key 实际使用的解码格式，解码失败时为 hex 或 base64
+    #[serde(rename = "keyFormat")]
+    pub key_format: Option<MessageFormat>,
+
+    #[serde(rename = "valueFormat")]
+    pub value_format: Option<MessageFormat>,
+
+    pub headers: Vec<RecordHeader>,
+
+    pub timestamp: Option<i64>,
+
+    #[serde(rename = "timestampType")]
+    pub timestamp_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordHeader {
+    pub key: String,
+
+    pub value: Option<String>,
+}
+
+/// 实时消费推送到前端的事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum TailEvent {
+    Records {
+        records: Vec<ConsumerRecord>,
+        scanned: u64,
+        matched: u64,
+    },
+    Error {
+        msg: String,
+    },
+    Stopped,
+}
+
+/// 搜索任务推送到前端的事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum SearchEvent {
+    Matched {
+        record: ConsumerRecord,
+    },
+    Progress {
+        partitions: Vec<PartitionSearchProgress>,
+        scanned: u64,
+        matched: u64,
+    },
+    Error {
+        partition: i32,
+        msg: String,
+    },
+    Finished {
+        scanned: u64,
+        matched: u64,
+        cancelled: bool,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PartitionSearchProgress {
+    pub partition: i32,
+
+    #[serde(rename = "startOffset")]
+    pub start_offset: i64,
+
+    #[serde(rename = "endOffset")]
+    pub end_offset: i64,
+
+    #[serde(rename = "currentOffset")]
+    pub current_offset: i64,
+
+    /// 已搜索的比例，0 到 100
+    pub percent: f64,
+}
@@ -0,0 +1,197 @@
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Default, Setters, Getters)]
+pub struct ConsumerGroupListResponse {
+    #[getset(get = "pub", set = "pub")]
+    pub current: i32,
+    #[getset(get = "pub", set = "pub")]
+    pub limit: i32,
+    #[getset(get = "pub", set = "pub")]
+    pub total: u64,
+    #[getset(get = "pub", set = "pub")]
+    pub list: Vec<ConsumerGroupResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumerGroupResponse {
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+
+    pub state: String,
+
+    #[serde(rename = "protocolType")]
+    pub protocol_type: String,
+
+    /// 成员数
+    pub members: usize,
+
+    /// 已提交位移的分区数
+    pub partitions: usize,
+
+    #[serde(rename = "totalLag")]
+    pub total_lag: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumerGroupDetail {
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+
+    pub state: String,
+
+    #[serde(rename = "protocolType")]
+    pub protocol_type: String,
+
+    /// 分配策略，如 range、cooperative-sticky
+    pub protocol: String,
+
+    pub coordinator: Option<BrokerNode>,
+
+    pub members: Vec<GroupMember>,
+
+    pub partitions: Vec<GroupPartitionLag>,
+
+    #[serde(rename = "totalLag")]
+    pub total_lag: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrokerNode {
+    pub id: i32,
+
+    pub host: String,
+
+    pub port: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupMember {
+    #[serde(rename = "memberId")]
+    pub member_id: String,
+
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+
+    pub host: String,
+
+    pub assignments: Vec<MemberAssignment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MemberAssignment {
+    pub topic: String,
+
+    pub partitions: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupPartitionLag {
+    pub topic: String,
+
+    pub partition: i32,
+
+    /// 已提交的位移，分配了但还没有提交时为空
+    #[serde(rename = "currentOffset")]
+    pub current_offset: Option<i64>,
+
+    #[serde(rename = "endOffset")]
+    pub end_offset: i64,
+
+    pub lag: Option<i64>,
+
+    pub leader: i32,
+
+    /// 消费该分区的成员
+    #[serde(rename = "consumerId")]
+    pub consumer_id: Option<String>,
+
+    #[serde(rename = "clientId")]
+    pub client_id: Option<String>,
+
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OffsetResetResponse {
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+
+    pub topic: String,
+
+    /// 是否已提交，dry-run 时为 false
+    pub applied: bool,
+
+    /// 提交前保存的位移备份，可用于回滚
+    #[serde(rename = "backupId")]
+    pub backup_id: Option<i32>,
+
+    pub partitions: Vec<OffsetResetItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OffsetResetItem {
+    pub partition: i32,
+
+    /// 当前已提交的位移，没有提交过时为空
+    #[serde(rename = "currentOffset")]
+    pub current_offset: Option<i64>,
+
+    #[serde(rename = "proposedOffset")]
+    pub proposed_offset: i64,
+
+    #[serde(rename = "lowWatermark")]
+    pub low_watermark: i64,
+
+    #[serde(rename = "highWatermark")]
+    pub high_watermark: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartitionOffset {
+    pub partition: i32,
+
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OffsetBackupResponse {
+    pub id: i32,
+
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+
+    pub topic: String,
+
+    pub offsets: Vec<PartitionOffset>,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupOperationResult {
+    pub name: String,
+
+    pub success: bool,
+
+    pub msg: String,
+}
+
+impl GroupOperationResult {
+    pub fn success(name: &str, msg: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            success: true,
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn error(name: &str, msg: String) -> Self {
+        Self {
+            name: name.to_string(),
+            success: false,
+            msg,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::entity::response::consumer_group::ConsumerGroupResponse;
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -6,7 +7,29 @@ pub struct DashboardStatistics {
     topic_count: StatisticsItem,
     partition_count: StatisticsItem,
     broker_count: StatisticsItem,
+    /// 所有分区保留的消息数，高水位减低水位
+    message_count: StatisticsItem,
+    /// 所有消费者组的 lag 之和，高水位减已提交位移
     lag_count: StatisticsItem,
+    /// lag 最大的消费者组
+    top_lag_groups: Vec<ConsumerGroupResponse>,
+    /// 水位查询失败或超时的分区，不计入消息数和 lag
+    unreachable_partitions: Vec<UnreachablePartition>,
+    /// 位移查询失败或超时的消费者组，不计入 lag
+    unreachable_groups: Vec<UnreachableGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnreachablePartition {
+    pub topic: String,
+    pub partition: i32,
+    pub msg: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnreachableGroup {
+    pub group_id: String,
+    pub msg: String,
 }
 
 #[allow(dead_code)]
@@ -23,10 +46,26 @@ impl DashboardStatistics {
         self.partition_count = StatisticsItem::create_partition_count_item(partition_count);
     }
 
+    pub fn set_message_count(&mut self, message_count: usize) {
+        self.message_count = StatisticsItem::create_message_count_item(message_count);
+    }
+
     pub fn set_lag_count(&mut self, lag_count: usize) {
         self.lag_count = StatisticsItem::create_lag_count_item(lag_count);
     }
 
+    pub fn set_top_lag_groups(&mut self, top_lag_groups: Vec<ConsumerGroupResponse>) {
+        self.top_lag_groups = top_lag_groups;
+    }
+
+    pub fn set_unreachable_partitions(&mut self, partitions: Vec<UnreachablePartition>) {
+        self.unreachable_partitions = partitions;
+    }
+
+    pub fn set_unreachable_groups(&mut self, groups: Vec<UnreachableGroup>) {
+        self.unreachable_groups = groups;
+    }
+
     pub fn get_broker_count(&self) -> usize {
         match self.broker_count.value.parse() {
             Ok(count) => count,
@@ -48,6 +87,13 @@ impl DashboardStatistics {
         }
     }
 
+    pub fn get_message_count(&self) -> usize {
+        match self.message_count.value.parse() {
+            Ok(count) => count,
+            Err(_) => 0,
+        }
+    }
+
     pub fn get_lag_count(&self) -> usize {
         match self.lag_count.value.parse() {
             Ok(count) => count,
@@ -92,12 +138,21 @@ impl StatisticsItem {
         }
     }
 
+    pub fn create_message_count_item(message_count: usize) -> Self {
+        Self {
+            title: "保留消息数".to_string(),
+            value: message_count.to_string(),
+            icon: "Document".to_string(),
+            color: "#909399".to_string(),
+        }
+    }
+
     pub fn create_lag_count_item(lag_count: usize) -> Self {
         Self {
-            title: "分区总偏移量".to_string(),
+            title: "总Lag".to_string(),
             value: lag_count.to_string(),
-            icon: "Partition".to_string(),
-            color: "#E6A23C".to_string(),
+            icon: "TrendCharts".to_string(),
+            color: "#F56C6C".to_string(),
         }
     }
 }
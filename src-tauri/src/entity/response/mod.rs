@@ -1,3 +1,8 @@
 pub mod cluster;
 pub mod common;
+pub mod consumer;
+pub mod consumer_group;
 pub mod dashboard;
+pub mod producer;
+pub mod registry;
+pub mod topic;
@@ -0,0 +1,73 @@
+use crate::entity::request::producer::ProduceMessageRequest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum DeliveryResult {
+    Delivered {
+        partition: i32,
+        offset: i64,
+        timestamp: Option<i64>,
+    },
+    Failed {
+        code: String,
+        msg: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProduceJobProgress {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+
+    pub total: u64,
+
+    pub sent: u64,
+
+    pub success: u64,
+
+    pub failed: u64,
+
+    /// 投递延迟百分位，毫秒
+    pub p50: u64,
+
+    pub p95: u64,
+
+    pub p99: u64,
+
+    pub finished: bool,
+
+    pub cancelled: bool,
+}
+
+impl ProduceJobProgress {
+    pub fn new(job_id: &str, total: u64) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            total,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FileProduceReport {
+    #[serde(rename = "totalRows")]
+    pub total_rows: usize,
+
+    #[serde(rename = "successRows")]
+    pub success_rows: usize,
+
+    #[serde(rename = "failedRows")]
+    pub failed_rows: Vec<FailedRow>,
+
+    /// dry-run 时解析出的消息
+    pub preview: Vec<ProduceMessageRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailedRow {
+    pub line: u64,
+
+    pub reason: String,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// schema registry 中的 schema 类型，registry 响应中缺省时为 AVRO
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SchemaType {
+    #[default]
+    Avro,
+    Protobuf,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegisteredSchema {
+    #[serde(default)]
+    pub subject: Option<String>,
+
+    /// 按 id 查询时 registry 不返回 id，由客户端补充
+    #[serde(default)]
+    pub id: i32,
+
+    #[serde(default)]
+    pub version: Option<i32>,
+
+    #[serde(rename = "schemaType", default)]
+    pub schema_type: SchemaType,
+
+    pub schema: String,
+
+    #[serde(default)]
+    pub references: Vec<SchemaReference>,
+}
+
+/// schema 引用的其他 subject，protobuf 中 name 为 import 路径
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaReference {
+    pub name: String,
+
+    pub subject: String,
+
+    pub version: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompatibilityResult {
+    #[serde(rename = "isCompatible", alias = "is_compatible")]
+    pub is_compatible: bool,
+
+    /// 不兼容的原因，registry 需要 verbose=true 才会返回
+    #[serde(default)]
+    pub messages: Vec<String>,
+}
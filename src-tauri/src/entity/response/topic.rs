@@ -0,0 +1,144 @@
+use derive_builder::Builder;
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Default, Setters, Getters)]
+pub struct TopicListResponse {
+    #[getset(get = "pub", set = "pub")]
+    pub current: i32,
+    #[getset(get = "pub", set = "pub")]
+    pub limit: i32,
+    #[getset(get = "pub", set = "pub")]
+    pub total: u64,
+    #[getset(get = "pub", set = "pub")]
+    pub list: Vec<TopicResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Builder, Clone)]
+pub struct TopicResponse {
+    pub name: String,
+
+    pub internal: bool,
+
+    #[serde(rename = "partitionCount")]
+    pub partition_count: usize,
+
+    #[serde(rename = "replicationFactor")]
+    pub replication_factor: usize,
+
+    #[serde(rename = "messageCount")]
+    pub message_count: i64,
+
+    pub partitions: Vec<PartitionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartitionResponse {
+    pub id: i32,
+
+    pub leader: i32,
+
+    pub replicas: Vec<i32>,
+
+    pub isr: Vec<i32>,
+
+    #[serde(rename = "lowOffset")]
+    pub low_offset: i64,
+
+    #[serde(rename = "highOffset")]
+    pub high_offset: i64,
+
+    #[serde(rename = "messageCount")]
+    pub message_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopicOperationResult {
+    pub name: String,
+
+    pub success: bool,
+
+    pub msg: String,
+}
+
+impl TopicOperationResult {
+    pub fn success(name: &str, msg: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            success: true,
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn error(name: &str, msg: String) -> Self {
+        Self {
+            name: name.to_string(),
+            success: false,
+            msg,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopicConfigEntry {
+    pub name: String,
+
+    pub value: Option<String>,
+
+    pub source: String,
+
+    #[serde(rename = "isDefault")]
+    pub is_default: bool,
+
+    #[serde(rename = "isReadOnly")]
+    pub is_read_only: bool,
+
+    #[serde(rename = "isSensitive")]
+    pub is_sensitive: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopicConfigChange {
+    pub name: String,
+
+    #[serde(rename = "oldValue")]
+    pub old_value: Option<String>,
+
+    #[serde(rename = "newValue")]
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartitionIncreaseResponse {
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+
+    #[serde(rename = "currentPartitionCount")]
+    pub current_partition_count: usize,
+
+    #[serde(rename = "newPartitionCount")]
+    pub new_partition_count: usize,
+
+    #[serde(rename = "sampledKeys")]
+    pub sampled_keys: usize,
+
+    #[serde(rename = "remappedKeys")]
+    pub remapped_keys: usize,
+
+    #[serde(rename = "remappedRatio")]
+    pub remapped_ratio: f64,
+
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartitionTruncateResult {
+    pub partition: i32,
+
+    #[serde(rename = "lowWatermark")]
+    pub low_watermark: Option<i64>,
+
+    pub success: bool,
+
+    pub msg: String,
+}
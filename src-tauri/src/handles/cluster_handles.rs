@@ -1,27 +1,27 @@
 use crate::entity::db_entity::cluster;
+use crate::entity::response::cluster::ClusterListResponse;
 use crate::entity::response::cluster::ClusterResponseBuilder;
 use crate::entity::response::common::CommonResponse;
-use crate::infra::kafka_infra::create_kafka_admin_client;
-use crate::infra::sql_infra::get_connect;
-use crate::{config::EasyKafkaConfig, entity::response::cluster::ClusterListResponse};
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::kafka_infra::{diagnose_connect, find_cluster};
+use crate::utils::security::{security_protocol, validate_security};
 use crate::{EasyKafkaError, EasyKafkaResult};
-use rdkafka::util::Timeout;
-use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, Set};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, Set,
+};
 use tauri::State;
-use tokio::time::Duration;
 use uuid::Uuid;
 
 #[tauri::command]
 pub async fn cluster_list(
     page: i32,
     limit: i32,
-    config: State<'_, EasyKafkaConfig>,
+    db: State<'_, DatabaseConnection>,
 ) -> EasyKafkaResult<ClusterListResponse> {
-    let db_connect = get_connect(&config.database).await?;
     let cluster = cluster::Entity::find()
         .offset(((page - 1) * limit) as u64)
         .limit(limit as u64)
-        .all(&db_connect)
+        .all(db.inner())
         .await?;
 
     let mut result = Vec::new();
@@ -30,10 +30,29 @@ pub async fn cluster_list(
             .id(item.id.clone())
             .servers(item.servers.clone())
             .cluster_name(item.cluster_name.clone())
+            .username(item.username.clone())
             .timeout(item.timeout)
             .ssl(item.ssl)
             .sasl(item.sasl.clone())
             .connected(item.connected)
+            .registry_url(item.registry_url.clone())
+            .registry_username(item.registry_username.clone())
+            .security_protocol(
+                security_protocol(&item)
+                    .map(|protocol| protocol.as_str().to_string())
+                    .ok()
+                    .or(item.security_protocol.clone()),
+            )
+            .ssl_ca_location(item.ssl_ca_location.clone())
+            .ssl_certificate_location(item.ssl_certificate_location.clone())
+            .ssl_key_location(item.ssl_key_location.clone())
+            .ssl_verify_hostname(item.ssl_verify_hostname)
+            .kerberos_keytab(item.kerberos_keytab.clone())
+            .kerberos_principal(item.kerberos_principal.clone())
+            .kerberos_service_name(item.kerberos_service_name.clone())
+            .oauth_token_endpoint(item.oauth_token_endpoint.clone())
+            .oauth_client_id(item.oauth_client_id.clone())
+            .oauth_scope(item.oauth_scope.clone())
             .build()
             .map_err(|e| {
                 EasyKafkaError::KafkaConnectNotFound(format!("Failed to build response: {:?}", e))
@@ -43,18 +62,55 @@ pub async fn cluster_list(
     let mut response = ClusterListResponse::default();
     response.set_current(page);
     response.set_limit(limit);
-    response.set_total(cluster::Entity::find().count(&db_connect).await?);
+    response.set_total(cluster::Entity::find().count(db.inner()).await?);
     response.set_list(result);
     Ok(response)
 }
 
 #[tauri::command]
 pub async fn cluster_create_or_update(
-    config: State<'_, EasyKafkaConfig>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
     model: cluster::Model,
 ) -> EasyKafkaResult<CommonResponse<String>> {
-    let db_connect = get_connect(&config.database).await?;
+    save_cluster(db.inner(), clients.inner(), model).await
+}
+
+async fn save_cluster(
+    db: &DatabaseConnection,
+    clients: &ClientRegistry,
+    mut model: cluster::Model,
+) -> EasyKafkaResult<CommonResponse<String>> {
     let is_new_record = model.id.is_empty();
+    if !is_new_record {
+        let stored = match cluster::Entity::find_by_id(model.id.as_str())
+            .one(db)
+            .await?
+        {
+            Some(stored) => stored,
+            None => return Ok(CommonResponse::error(format!("集群[{}]不存在", model.id))),
+        };
+        // 列表接口不返回密码类字段，编辑时没有重新填写或为空则沿用已保存的值
+        model.password = model
+            .password
+            .filter(|item| !item.is_empty())
+            .or(stored.password);
+        model.registry_password = model
+            .registry_password
+            .filter(|item| !item.is_empty())
+            .or(stored.registry_password);
+        model.ssl_key_password = model
+            .ssl_key_password
+            .filter(|item| !item.is_empty())
+            .or(stored.ssl_key_password);
+        model.oauth_client_secret = model
+            .oauth_client_secret
+            .filter(|item| !item.is_empty())
+            .or(stored.oauth_client_secret);
+    }
+    if let Err(msg) = validate_security(&model) {
+        return Ok(CommonResponse::error(msg));
+    }
 
     let active_model = cluster::ActiveModel {
         id: Set(if is_new_record {
@@ -70,15 +126,30 @@ pub async fn cluster_create_or_update(
         ssl: Set(model.ssl),
         sasl: Set(model.sasl),
         connected: Set(model.connected),
+        registry_url: Set(model.registry_url.filter(|item| !item.is_empty())),
+        registry_username: Set(model.registry_username),
+        registry_password: Set(model.registry_password),
+        security_protocol: Set(model.security_protocol.filter(|item| !item.is_empty())),
+        ssl_ca_location: Set(model.ssl_ca_location),
+        ssl_certificate_location: Set(model.ssl_certificate_location),
+        ssl_key_location: Set(model.ssl_key_location),
+        ssl_key_password: Set(model.ssl_key_password),
+        ssl_verify_hostname: Set(model.ssl_verify_hostname),
+        kerberos_keytab: Set(model.kerberos_keytab),
+        kerberos_principal: Set(model.kerberos_principal),
+        kerberos_service_name: Set(model.kerberos_service_name),
+        oauth_token_endpoint: Set(model.oauth_token_endpoint),
+        oauth_client_id: Set(model.oauth_client_id),
+        oauth_client_secret: Set(model.oauth_client_secret),
+        oauth_scope: Set(model.oauth_scope),
     };
     if is_new_record {
-        cluster::Entity::insert(active_model)
-            .exec(&db_connect)
-            .await?;
+        cluster::Entity::insert(active_model).exec(db).await?;
     } else {
-        cluster::Entity::update(active_model)
-            .exec(&db_connect)
-            .await?;
+        let token = active_model.id.clone().unwrap();
+        cluster::Entity::update(active_model).exec(db).await?;
+        // 集群配置修改后按新配置重新创建客户端
+        clients.invalidate(&token);
     };
     Ok(CommonResponse::success("操作成功".to_string()))
 }
@@ -86,36 +157,141 @@ pub async fn cluster_create_or_update(
 #[tauri::command]
 pub async fn check_connect(
     token: &str,
-    config: State<'_, EasyKafkaConfig>,
+    db: State<'_, DatabaseConnection>,
 ) -> EasyKafkaResult<CommonResponse<String>> {
     if token.is_empty() {
         return Ok(CommonResponse::error("token不能为空".to_string()));
     }
-    let (admin_client, connect) = create_kafka_admin_client(token, &config).await?;
-    match admin_client.inner().fetch_metadata(
-        None,
-        Timeout::from(Duration::from_millis(connect.timeout as u64)),
-    ) {
-        Ok(metadata) => Ok(CommonResponse::success(format!(
+    let connect = find_cluster(token, &db).await?;
+    if let Err(msg) = validate_security(&connect) {
+        return Ok(CommonResponse::error(msg));
+    }
+    match tokio::task::spawn_blocking(move || diagnose_connect(&connect)).await?? {
+        Ok(brokers) => Ok(CommonResponse::success(format!(
             "连接成功，broker数量: {}",
-            metadata.brokers().len()
+            brokers
         ))),
-        Err(_) => Ok(CommonResponse::error("Kafka连接失败".to_string())),
+        Err(msg) => Ok(CommonResponse::error(msg)),
     }
 }
 
 #[tauri::command]
 pub async fn delete_cluster(
     token: &str,
-    config: State<'_, EasyKafkaConfig>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
 ) -> EasyKafkaResult<CommonResponse<String>> {
     if token.is_empty() {
         return Ok(CommonResponse::error("token不能为空".to_string()));
     }
-    let db_connect = get_connect(&config.database).await?;
     cluster::Entity::delete_many()
         .filter(cluster::Column::Id.eq(token))
-        .exec(&db_connect)
+        .exec(db.inner())
         .await?;
+    clients.invalidate(token);
     Ok(CommonResponse::success("删除成功".to_string()))
 }
+
+#[cfg(test)]
+mod cluster_handles_test {
+    use crate::config::DatabaseConfig;
+    use crate::entity::db_entity::cluster;
+    use crate::handles::cluster_handles::save_cluster;
+    use crate::infra::client_registry::ClientRegistry;
+    use crate::migration::migrate;
+    use sea_orm::{ConnectOptions, Database, EntityTrait};
+    use std::time::Duration;
+
+    fn connect() -> cluster::Model {
+        cluster::Model {
+            id: String::new(),
+            servers: "localhost:9092".to_string(),
+            cluster_name: "local".to_string(),
+            username: Some("admin".to_string()),
+            password: Some("admin-secret".to_string()),
+            timeout: 5000,
+            ssl: false,
+            sasl: Some("PLAIN".to_string()),
+            connected: false,
+            registry_url: Some("http://localhost:8081".to_string()),
+            registry_username: Some("registry".to_string()),
+            registry_password: Some("registry-secret".to_string()),
+            security_protocol: Some("SASL_PLAINTEXT".to_string()),
+            ssl_ca_location: None,
+            ssl_certificate_location: None,
+            ssl_key_location: None,
+            ssl_key_password: Some("key-secret".to_string()),
+            ssl_verify_hostname: true,
+            kerberos_keytab: None,
+            kerberos_principal: None,
+            kerberos_service_name: None,
+            oauth_token_endpoint: None,
+            oauth_client_id: None,
+            oauth_client_secret: Some("oauth-secret".to_string()),
+            oauth_scope: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn keep_stored_secret_test() {
+        let url = "sqlite::memory:";
+        let mut options = ConnectOptions::new(url);
+        options.max_connections(1).min_connections(1);
+        let db = Database::connect(options).await.unwrap();
+        let config = DatabaseConfig {
+            url: url.to_string(),
+            max_connections: 1,
+            min_connections: 1,
+            connect_timeout: 5,
+            idle_timeout: 60,
+            max_lifetime: 60,
+            sqlx_logging: false,
+        };
+        migrate(&db, &config).await.unwrap();
+        let clients = ClientRegistry::new(Duration::from_secs(60));
+
+        let response = save_cluster(&db, &clients, connect()).await.unwrap();
+        assert_eq!(response.code, 200, "{}", response.msg);
+        let stored = cluster::Entity::find().one(&db).await.unwrap().unwrap();
+
+        // 编辑时前端没有回填密码类字段，提交的是空字符串或不传
+        let mut model = connect();
+        model.id = stored.id.clone();
+        model.cluster_name = "renamed".to_string();
+        model.password = Some(String::new());
+        model.registry_password = Some(String::new());
+        model.ssl_key_password = None;
+        model.oauth_client_secret = Some(String::new());
+        let response = save_cluster(&db, &clients, model).await.unwrap();
+        assert_eq!(response.code, 200, "{}", response.msg);
+
+        let updated = cluster::Entity::find_by_id(stored.id.as_str())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.cluster_name, "renamed");
+        assert_eq!(updated.password.as_deref(), Some("admin-secret"));
+        assert_eq!(
+            updated.registry_password.as_deref(),
+            Some("registry-secret")
+        );
+        assert_eq!(updated.ssl_key_password.as_deref(), Some("key-secret"));
+        assert_eq!(updated.oauth_client_secret.as_deref(), Some("oauth-secret"));
+
+        // 重新填写时使用新的值
+        let mut model = updated.clone();
+        model.password = Some("changed".to_string());
+        save_cluster(&db, &clients, model).await.unwrap();
+        let changed = cluster::Entity::find_by_id(stored.id.as_str())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(changed.password.as_deref(), Some("changed"));
+        assert_eq!(
+            changed.registry_password.as_deref(),
+            Some("registry-secret")
+        );
+    }
+}
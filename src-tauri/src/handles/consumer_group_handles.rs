@@ -0,0 +1,466 @@
+use crate::entity::db_entity::offset_backups;
+use crate::entity::request::consumer_group::{
+    DeleteGroupOffsetsRequest, DeleteGroupsRequest, GroupSelection, ResetOffsetsRequest,
+};
+use crate::entity::response::common::CommonResponse;
+use crate::entity::response::consumer_group::{
+    ConsumerGroupDetail, ConsumerGroupListResponse, GroupOperationResult, OffsetBackupResponse,
+    OffsetResetResponse, PartitionOffset,
+};
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::group_infra::{
+    commit_group_offsets, count_active_members, current_millis, delete_committed_offsets,
+    describe_group, describe_offsets, fetch_group_list, fetch_partition_committed, is_live_group,
+    plan_offset_reset, select_groups, summarize_group, HighWatermarks,
+};
+use crate::infra::kafka_infra::resolve_partitions;
+use crate::{EasyKafkaError, EasyKafkaResult};
+use log::info;
+use rdkafka::admin::AdminOptions;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::util::Timeout;
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tauri::State;
+use tokio::time::Duration;
+
+#[tauri::command]
+pub async fn consumer_group_list(
+    token: &str,
+    page: i32,
+    limit: i32,
+    search: Option<String>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<ConsumerGroupListResponse> {
+    info!("consumer_group_list token: {}, search: {:?}", token, search);
+    if page <= 0 || limit <= 0 {
+        return Err(EasyKafkaError::InvalidParam(
+            "page和limit必须大于0".to_string(),
+        ));
+    }
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let search = search
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty());
+    tokio::task::spawn_blocking(move || -> EasyKafkaResult<ConsumerGroupListResponse> {
+        let group_list = fetch_group_list(&consumer, None, timeout)?;
+        let mut groups = group_list
+            .groups()
+            .iter()
+            .filter(|group| is_live_group(group))
+            .filter(|group| match &search {
+                Some(search) => group.name().to_lowercase().contains(search),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // 只为当前页的消费者组查询位移和 lag
+        let mut result = Vec::new();
+        let page_groups = groups
+            .iter()
+            .skip((page as usize - 1) * limit as usize)
+            .take(limit as usize)
+            .collect::<Vec<_>>();
+        if !page_groups.is_empty() {
+            let metadata = consumer.fetch_metadata(None, Timeout::from(timeout))?;
+            let mut watermarks = HighWatermarks::new(&consumer, timeout);
+            for group in page_groups {
+                result.push(summarize_group(
+                    &consumer,
+                    group,
+                    &metadata,
+                    &mut watermarks,
+                    timeout,
+                )?);
+            }
+        }
+        let mut response = ConsumerGroupListResponse::default();
+        response.set_current(page);
+        response.set_limit(limit);
+        response.set_total(groups.len() as u64);
+        response.set_list(result);
+        Ok(response)
+    })
+    .await?
+}
+
+/// 查询消费者组的成员、coordinator 和每个分区的 lag
+#[tauri::command]
+pub async fn consumer_group_detail(
+    token: &str,
+    group_id: &str,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<ConsumerGroupDetail>> {
+    if group_id.is_empty() {
+        return Ok(CommonResponse::error("消费者组id不能为空".to_string()));
+    }
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let group = group_id.to_string();
+    let detail =
+        tokio::task::spawn_blocking(move || describe_group(&consumer, &group, timeout)).await??;
+    match detail {
+        Some(detail) => Ok(CommonResponse::success(detail)),
+        None => Ok(CommonResponse::error(format!(
+            "消费者组[{}]不存在",
+            group_id
+        ))),
+    }
+}
+
+/// 重置消费者组在 topic 上的位移，提交前备份当前位移，dry-run 时只返回重置前后的位移
+#[tauri::command]
+pub async fn reset_group_offsets(
+    token: &str,
+    request: ResetOffsetsRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<OffsetResetResponse>> {
+    if request.group_id.is_empty() || request.topic.is_empty() {
+        return Ok(CommonResponse::error(
+            "消费者组id和topic名称不能为空".to_string(),
+        ));
+    }
+    info!(
+        "reset_group_offsets token: {}, request: {:?}",
+        token, request
+    );
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let plan_request = request.clone();
+    let plan = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Result<_, String>> {
+        let partitions = match resolve_partitions(
+            &consumer,
+            &plan_request.topic,
+            plan_request.partitions.as_deref(),
+            timeout,
+        )? {
+            Ok(partitions) => partitions,
+            Err(msg) => return Ok(Err(msg)),
+        };
+        if !plan_request.dry_run {
+            if let Err(msg) = check_inactive(&consumer, &plan_request.group_id, timeout)? {
+                return Ok(Err(msg));
+            }
+        }
+        plan_offset_reset(&consumer, &plan_request, &partitions, timeout).map(Ok)
+    })
+    .await??;
+    let items = match plan {
+        Ok(items) => items,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let mut response = OffsetResetResponse {
+        group_id: request.group_id.clone(),
+        topic: request.topic.clone(),
+        applied: false,
+        backup_id: None,
+        partitions: items,
+    };
+    if request.dry_run {
+        return Ok(CommonResponse::success(response));
+    }
+
+    // 提交前备份当前已提交的位移，没有提交过位移的分区不在备份中
+    let backup = response
+        .partitions
+        .iter()
+        .filter_map(|item| {
+            item.current_offset.map(|offset| PartitionOffset {
+                partition: item.partition,
+                offset,
+            })
+        })
+        .collect::<Vec<_>>();
+    let backup = offset_backups::ActiveModel {
+        id: NotSet,
+        cluster_id: Set(token.to_string()),
+        group_id: Set(request.group_id.clone()),
+        topic: Set(request.topic.clone()),
+        offsets: Set(serde_json::to_string(&backup)?),
+        created_at: Set(current_millis()),
+    }
+    .insert(db.inner())
+    .await?;
+    response.backup_id = Some(backup.id);
+
+    let offsets = response
+        .partitions
+        .iter()
+        .map(|item| PartitionOffset {
+            partition: item.partition,
+            offset: item.proposed_offset,
+        })
+        .collect::<Vec<_>>();
+    tokio::task::spawn_blocking(move || {
+        commit_group_offsets(&connect, &request.group_id, &request.topic, &offsets)
+    })
+    .await??;
+    response.applied = true;
+    info!(
+        "reset_group_offsets group: {}, topic: {}, backup: {}",
+        response.group_id, response.topic, backup.id
+    );
+    Ok(CommonResponse::success(response))
+}
+
+/// 查询位移备份，group_id 为空时返回集群的所有备份
+#[tauri::command]
+pub async fn offset_backup_list(
+    token: &str,
+    group_id: Option<String>,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<Vec<OffsetBackupResponse>>> {
+    let mut query = offset_backups::Entity::find()
+        .filter(offset_backups::Column::ClusterId.eq(token))
+        .order_by_desc(offset_backups::Column::CreatedAt);
+    if let Some(group_id) = group_id.filter(|item| !item.is_empty()) {
+        query = query.filter(offset_backups::Column::GroupId.eq(group_id));
+    }
+    let mut result = Vec::new();
+    for model in query.all(db.inner()).await? {
+        result.push(OffsetBackupResponse {
+            id: model.id,
+            group_id: model.group_id,
+            topic: model.topic,
+            offsets: serde_json::from_str(&model.offsets)?,
+            created_at: model.created_at,
+        });
+    }
+    Ok(CommonResponse::success(result))
+}
+
+/// 将消费者组的位移回滚到备份时的状态
+#[tauri::command]
+pub async fn restore_group_offsets(
+    token: &str,
+    backup_id: i32,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<OffsetResetResponse>> {
+    let backup = match offset_backups::Entity::find_by_id(backup_id)
+        .filter(offset_backups::Column::ClusterId.eq(token))
+        .one(db.inner())
+        .await?
+    {
+        Some(backup) => backup,
+        None => return Ok(CommonResponse::error(format!("备份[{}]不存在", backup_id))),
+    };
+    let offsets: Vec<PartitionOffset> = serde_json::from_str(&backup.offsets)?;
+    if offsets.is_empty() {
+        return Ok(CommonResponse::error("备份中没有已提交的位移".to_string()));
+    }
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let group_id = backup.group_id.clone();
+    let topic = backup.topic.clone();
+    let restored = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Result<_, String>> {
+        if let Err(msg) = check_inactive(&consumer, &group_id, timeout)? {
+            return Ok(Err(msg));
+        }
+        let partitions = offsets
+            .iter()
+            .map(|item| item.partition)
+            .collect::<Vec<_>>();
+        let current =
+            fetch_partition_committed(&consumer, &group_id, &topic, &partitions, timeout)?;
+        let items = describe_offsets(&consumer, &topic, &current, &offsets, timeout)?;
+        commit_group_offsets(&connect, &group_id, &topic, &offsets)?;
+        Ok(Ok(items))
+    })
+    .await??;
+    match restored {
+        Ok(items) => {
+            info!(
+                "restore_group_offsets group: {}, topic: {}, backup: {}",
+                backup.group_id, backup.topic, backup.id
+            );
+            Ok(CommonResponse::success(OffsetResetResponse {
+                group_id: backup.group_id,
+                topic: backup.topic,
+                applied: true,
+                backup_id: Some(backup.id),
+                partitions: items,
+            }))
+        }
+        Err(msg) => Ok(CommonResponse::error(msg)),
+    }
+}
+
+/// 批量删除消费者组，有成员的消费者组会被 broker 拒绝
+#[tauri::command]
+pub async fn delete_consumer_groups(
+    token: &str,
+    request: DeleteGroupsRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<GroupOperationResult>>> {
+    let regex = match group_pattern(&request.selection) {
+        Ok(regex) => regex,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let (consumer, _) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let selection = request.selection.clone();
+    let (selected, mut results) = tokio::task::spawn_blocking(move || -> EasyKafkaResult<_> {
+        let group_list = fetch_group_list(&consumer, None, timeout)?;
+        Ok(select_groups(&group_list, &selection, regex.as_ref()))
+    })
+    .await??;
+
+    if request.dry_run || selected.is_empty() {
+        results.extend(
+            selected
+                .iter()
+                .map(|name| GroupOperationResult::success(name, "将被删除")),
+        );
+        return Ok(CommonResponse::success(results));
+    }
+
+    info!(
+        "delete_consumer_groups token: {}, groups: {:?}",
+        token, selected
+    );
+    let options = AdminOptions::new().operation_timeout(Some(timeout));
+    let names = selected
+        .iter()
+        .map(|item| item.as_str())
+        .collect::<Vec<_>>();
+    for result in admin_client.delete_groups(&names, &options).await? {
+        match result {
+            Ok(name) => results.push(GroupOperationResult::success(&name, "删除成功")),
+            Err((name, code)) => results.push(GroupOperationResult::error(
+                &name,
+                format!("删除失败: {}", code),
+            )),
+        }
+    }
+    Ok(CommonResponse::success(results))
+}
+
+/// 批量删除消费者组在 topic 上已提交的位移，正在订阅该 topic 的消费者组会被 broker 拒绝
+#[tauri::command]
+pub async fn delete_group_offsets(
+    token: &str,
+    request: DeleteGroupOffsetsRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<GroupOperationResult>>> {
+    if request.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let regex = match group_pattern(&request.selection) {
+        Ok(regex) => regex,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    info!(
+        "delete_group_offsets token: {}, request: {:?}",
+        token, request
+    );
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let deleted = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Result<_, String>> {
+        let partitions = match resolve_partitions(
+            &consumer,
+            &request.topic,
+            request.partitions.as_deref(),
+            timeout,
+        )? {
+            Ok(partitions) => partitions,
+            Err(msg) => return Ok(Err(msg)),
+        };
+        let group_list = fetch_group_list(&consumer, None, timeout)?;
+        let (selected, mut results) =
+            select_groups(&group_list, &request.selection, regex.as_ref());
+        for group_id in selected {
+            let committed = fetch_partition_committed(
+                &consumer,
+                &group_id,
+                &request.topic,
+                &partitions,
+                timeout,
+            )?;
+            // 正则匹配到但没有消费过该 topic 的消费者组不出现在结果中
+            if committed.is_empty() {
+                if request.selection.group_ids.contains(&group_id) {
+                    results.push(GroupOperationResult::error(
+                        &group_id,
+                        "没有提交过该topic的位移".to_string(),
+                    ));
+                }
+                continue;
+            }
+            let mut committed_partitions = committed.into_keys().collect::<Vec<_>>();
+            committed_partitions.sort();
+            if request.dry_run {
+                results.push(GroupOperationResult::success(
+                    &group_id,
+                    &format!("将删除{}个分区的位移", committed_partitions.len()),
+                ));
+                continue;
+            }
+            match delete_committed_offsets(
+                &consumer,
+                &group_id,
+                &request.topic,
+                &committed_partitions,
+                timeout,
+            ) {
+                Ok(()) => results.push(GroupOperationResult::success(
+                    &group_id,
+                    &format!("已删除{}个分区的位移", committed_partitions.len()),
+                )),
+                Err(msg) => results.push(GroupOperationResult::error(
+                    &group_id,
+                    format!("删除失败: {}", msg),
+                )),
+            }
+        }
+        Ok(Ok(results))
+    })
+    .await??;
+    match deleted {
+        Ok(results) => Ok(CommonResponse::success(results)),
+        Err(msg) => Ok(CommonResponse::error(msg)),
+    }
+}
+
+/// 校验批量选择条件，正则需要完整匹配消费者组id，避免误删
+fn group_pattern(selection: &GroupSelection) -> Result<Option<Regex>, String> {
+    let pattern = selection
+        .pattern
+        .as_deref()
+        .filter(|item| !item.trim().is_empty());
+    if selection.group_ids.is_empty() && pattern.is_none() {
+        return Err("请指定要操作的消费者组".to_string());
+    }
+    pattern
+        .map(|item| Regex::new(&format!("^(?:{})$", item)))
+        .transpose()
+        .map_err(|e| format!("正则表达式错误: {}", e))
+}
+
+/// 消费者组有活跃成员时不能修改位移
+fn check_inactive(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    timeout: Duration,
+) -> EasyKafkaResult<Result<(), String>> {
+    let members = count_active_members(consumer, group_id, timeout)?;
+    if members > 0 {
+        return Ok(Err(format!(
+            "消费者组[{}]有{}个活跃成员，请先停止消费者",
+            group_id, members
+        )));
+    }
+    Ok(Ok(()))
+}
@@ -0,0 +1,202 @@
+use crate::entity::request::consumer::{ConsumeMessagesRequest, SearchTopicRequest};
+use crate::entity::response::common::CommonResponse;
+use crate::entity::response::consumer::{ConsumeMessagesResponse, SearchEvent, TailEvent};
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::executor::{run_search_job, JobExecutor, TailSessions, TailState};
+use crate::infra::kafka_infra::{
+    create_kafka_consumer, poll_records, resolve_partitions, resolve_start_offsets,
+};
+use crate::utils::message_filter::MessageFilter;
+use crate::EasyKafkaResult;
+use log::info;
+use sea_orm::DatabaseConnection;
+use tauri::ipc::Channel;
+use tauri::State;
+use tokio::time::Duration;
+
+/// 按分区和起始位置浏览消息，使用手动分配分区的消费者，不提交位移
+#[tauri::command]
+pub async fn consume_messages(
+    token: &str,
+    request: ConsumeMessagesRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<ConsumeMessagesResponse>> {
+    if request.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let filter = match compile_filter(request.filter.as_deref()) {
+        Ok(filter) => filter,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    info!("consume_messages token: {}, request: {:?}", token, request);
+    let codec = clients.codec(token, &request.topic, &db).await?;
+    let (consumer, connect) = create_kafka_consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let records = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Result<_, String>> {
+        let partitions = match resolve_partitions(
+            &consumer,
+            &request.topic,
+            request.partitions.as_deref(),
+            timeout,
+        )? {
+            Ok(partitions) => partitions,
+            Err(msg) => return Ok(Err(msg)),
+        };
+        let offsets = resolve_start_offsets(
+            &consumer,
+            &request.topic,
+            &partitions,
+            &request.position,
+            request.limit,
+            timeout,
+        )?;
+        poll_records(
+            &consumer,
+            &request.topic,
+            &offsets,
+            request.limit,
+            filter.as_ref(),
+            &codec,
+            timeout,
+        )
+        .map(Ok)
+    })
+    .await??;
+
+    match records {
+        Ok(records) => Ok(CommonResponse::success(records)),
+        Err(msg) => Ok(CommonResponse::error(msg)),
+    }
+}
+
+/// 开始实时消费 topic，消息通过 channel 推送，返回会话 id
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn tail_topic(
+    token: &str,
+    topic: String,
+    partitions: Option<Vec<i32>>,
+    filter: Option<String>,
+    channel: Channel<TailEvent>,
+    sessions: State<'_, TailSessions>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let filter = match compile_filter(filter.as_deref()) {
+        Ok(filter) => filter,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let codec = clients.codec(token, &topic, &db).await?;
+    let (consumer, connect) = create_kafka_consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let resolve_topic = topic.clone();
+    let (consumer, partitions) = tokio::task::spawn_blocking(move || -> EasyKafkaResult<_> {
+        let partitions =
+            resolve_partitions(&consumer, &resolve_topic, partitions.as_deref(), timeout)?;
+        Ok((consumer, partitions))
+    })
+    .await??;
+    let partitions = match partitions {
+        Ok(partitions) => partitions,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let session_id = sessions.start(consumer, &topic, &partitions, filter, codec, channel)?;
+    info!("tail_topic session started: {}", session_id);
+    Ok(CommonResponse::success(session_id))
+}
+
+/// 提交全 topic 搜索任务，匹配的消息和各分区进度通过 channel 推送，返回任务 id
+#[tauri::command]
+pub async fn search_topic(
+    token: &str,
+    request: SearchTopicRequest,
+    channel: Channel<SearchEvent>,
+    executor: State<'_, JobExecutor>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if request.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let filter = match compile_filter(Some(&request.filter)) {
+        Ok(Some(filter)) => filter,
+        Ok(None) => return Ok(CommonResponse::error("过滤表达式不能为空".to_string())),
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    if let (Some(start), Some(end)) = (request.start_timestamp, request.end_timestamp) {
+        if start > end {
+            return Ok(CommonResponse::error(
+                "开始时间不能晚于结束时间".to_string(),
+            ));
+        }
+    }
+    info!("search_topic token: {}, request: {:?}", token, request);
+    let codec = clients.codec(token, &request.topic, &db).await?;
+    let (consumer, connect) = create_kafka_consumer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let topic = request.topic.clone();
+    let selected = request.partitions.clone();
+    let partitions = tokio::task::spawn_blocking(move || {
+        resolve_partitions(&consumer, &topic, selected.as_deref(), timeout)
+    })
+    .await??;
+    let partitions = match partitions {
+        Ok(partitions) => partitions,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let job_id = executor.submit(|_, signal| {
+        run_search_job(channel, connect, request, partitions, filter, codec, signal)
+    });
+    info!("search_topic job submitted: {}", job_id);
+    Ok(CommonResponse::success(job_id))
+}
+
+#[tauri::command]
+pub async fn tail_pause(
+    session_id: &str,
+    sessions: State<'_, TailSessions>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    Ok(change_tail_state(&sessions, session_id, TailState::Paused))
+}
+
+#[tauri::command]
+pub async fn tail_resume(
+    session_id: &str,
+    sessions: State<'_, TailSessions>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    Ok(change_tail_state(&sessions, session_id, TailState::Running))
+}
+
+#[tauri::command]
+pub async fn tail_stop(
+    session_id: &str,
+    sessions: State<'_, TailSessions>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    Ok(change_tail_state(&sessions, session_id, TailState::Stopped))
+}
+
+fn change_tail_state(
+    sessions: &TailSessions,
+    session_id: &str,
+    state: TailState,
+) -> CommonResponse<String> {
+    if sessions.set_state(session_id, state) {
+        CommonResponse::success("操作成功".to_string())
+    } else {
+        CommonResponse::error(format!("会话[{}]不存在或已结束", session_id))
+    }
+}
+
+/// 编译过滤表达式，表达式为空时不过滤
+fn compile_filter(filter: Option<&str>) -> Result<Option<MessageFilter>, String> {
+    match filter.map(str::trim).filter(|item| !item.is_empty()) {
+        Some(expression) => MessageFilter::compile(expression)
+            .map(Some)
+            .map_err(|e| format!("过滤表达式错误: {}", e)),
+        None => Ok(None),
+    }
+}
@@ -1,58 +1,42 @@
 use crate::{
-    config::EasyKafkaConfig, entity::response::dashboard::DashboardStatistics,
-    infra::kafka_infra::create_kafka_admin_client,
+    entity::response::dashboard::DashboardStatistics, infra::client_registry::ClientRegistry,
+    infra::dashboard_infra::collect_statistics,
 };
 use log::info;
-use rdkafka::util::Timeout;
-use std::time::Duration;
+use sea_orm::DatabaseConnection;
 use tauri::State;
+use tokio::time::{Duration, Instant};
 
 use crate::EasyKafkaResult;
 
+/// 默认返回 lag 最大的消费者组数量
+const DEFAULT_TOP_LAG_GROUPS: usize = 5;
+/// 默认的统计截止时间，超过后返回已经查询到的部分结果
+const DEFAULT_DEADLINE_MILLIS: u64 = 30_000;
+/// 统计截止时间的上限，避免过大的值导致时间计算溢出
+const MAX_DEADLINE_MILLIS: u64 = 300_000;
+
 #[tauri::command]
 pub async fn dashboard_statistics(
     token: &str,
-    config: State<'_, EasyKafkaConfig>,
+    top: Option<usize>,
+    deadline_millis: Option<u64>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
 ) -> EasyKafkaResult<DashboardStatistics> {
     info!("dashboard_statistics token: {}", token);
-    let (admin_client, connect) = create_kafka_admin_client(token, &config).await?;
-    let mut result = DashboardStatistics::default();
-
-    let metadata = admin_client.inner().fetch_metadata(
-        None,
-        Timeout::from(Duration::from_secs(connect.timeout as u64)),
-    )?;
-    // broker
-    result.set_broker_count(metadata.brokers().len());
-
-    // topic
-    let topics = metadata.topics();
-    result.set_topic_count(topics.len());
-
-    if !topics.is_empty() {
-        for topic in topics {
-            let partitions = topic.partitions();
-            // partition
-            result.set_partition_count(result.get_partition_count() + partitions.len());
-            // lag
-            let count = result.get_lag_count()
-                + partitions
-                    .iter()
-                    .map(|item| {
-                        let (high, low) = admin_client.inner().fetch_watermarks(
-                            topic.name(),
-                            item.id(),
-                            Timeout::from(Duration::from_secs(5)),
-                        )?;
-                        Ok((high - low) as usize)
-                    })
-                    .sum::<EasyKafkaResult<usize>>()?;
-            result.set_lag_count(count);
-        }
-    } else {
-        result.set_partition_count(0);
-        result.set_lag_count(0);
-    }
+    let (consumer, connect) = clients.consumer(token, &db).await?;
+    let deadline_millis = deadline_millis
+        .unwrap_or(DEFAULT_DEADLINE_MILLIS)
+        .min(MAX_DEADLINE_MILLIS);
+    let deadline = Instant::now() + Duration::from_millis(deadline_millis);
+    let result = collect_statistics(
+        consumer,
+        connect,
+        deadline,
+        top.unwrap_or(DEFAULT_TOP_LAG_GROUPS),
+    )
+    .await?;
     info!("dashboard_statistics result: {:?}", result);
     Ok(result)
 }
@@ -0,0 +1,19 @@
+use crate::entity::response::common::CommonResponse;
+use crate::infra::executor::JobExecutor;
+use crate::EasyKafkaResult;
+use tauri::State;
+
+#[tauri::command]
+pub async fn cancel_job(
+    job_id: &str,
+    executor: State<'_, JobExecutor>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if executor.cancel(job_id) {
+        Ok(CommonResponse::success("任务已取消".to_string()))
+    } else {
+        Ok(CommonResponse::error(format!(
+            "任务[{}]不存在或已结束",
+            job_id
+        )))
+    }
+}
@@ -1,3 +1,8 @@
 pub mod cluster_handles;
+pub mod consumer_group_handles;
+pub mod consumer_handles;
 pub mod dashboard_handles;
+pub mod job_handles;
+pub mod producer_handles;
+pub mod registry_handles;
 pub mod topic_handles;
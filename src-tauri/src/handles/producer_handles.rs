@@ -0,0 +1,198 @@
+use crate::entity::request::producer::{
+    BatchProduceRequest, FileProduceRequest, ProduceMessageRequest,
+};
+use crate::entity::response::common::CommonResponse;
+use crate::entity::response::producer::{DeliveryResult, FailedRow, FileProduceReport};
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::executor::{run_produce_job, JobExecutor};
+use crate::infra::kafka_infra::send_message;
+use crate::utils::record_file::{map_record, parse_records};
+use crate::EasyKafkaResult;
+use log::info;
+use sea_orm::DatabaseConnection;
+use tauri::{AppHandle, State};
+use tokio::task::JoinSet;
+use tokio::time::Duration;
+
+/// 文件发送时同时等待投递结果的最大消息数
+const FILE_PRODUCE_IN_FLIGHT: usize = 1000;
+/// 批量发送支持的速率范围，条/秒
+const MIN_PRODUCE_RATE: f64 = 0.01;
+const MAX_PRODUCE_RATE: f64 = 100_000.0;
+/// 批量发送支持的最大发送间隔，毫秒
+const MAX_PRODUCE_INTERVAL: u64 = 3_600_000;
+
+#[tauri::command]
+pub async fn produce_message(
+    token: &str,
+    message: ProduceMessageRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<DeliveryResult>> {
+    if message.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let codec = clients.codec(token, &message.topic, &db).await?;
+    let (producer, connect) = clients.producer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let result = send_message(&producer, &message, &codec, timeout).await;
+    info!(
+        "produce_message topic: {}, result: {:?}",
+        message.topic, result
+    );
+    match &result {
+        DeliveryResult::Delivered { .. } => Ok(CommonResponse::success(result)),
+        DeliveryResult::Failed { msg, .. } => {
+            Ok(CommonResponse::error_with_data(msg.clone(), result))
+        }
+    }
+}
+
+/// 提交批量发送任务，返回任务 id，进度通过 produce-job-progress 事件推送
+#[tauri::command]
+pub async fn produce_batch(
+    token: &str,
+    request: BatchProduceRequest,
+    app: AppHandle,
+    executor: State<'_, JobExecutor>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if request.message.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    if request.count == 0 {
+        return Ok(CommonResponse::error("消息数必须大于0".to_string()));
+    }
+    if let Some(rate) = request.rate {
+        if !(MIN_PRODUCE_RATE..=MAX_PRODUCE_RATE).contains(&rate) {
+            return Ok(CommonResponse::error(format!(
+                "发送速率必须在{}到{}条/秒之间",
+                MIN_PRODUCE_RATE, MAX_PRODUCE_RATE
+            )));
+        }
+    }
+    if request
+        .interval
+        .is_some_and(|interval| interval > MAX_PRODUCE_INTERVAL)
+    {
+        return Ok(CommonResponse::error(format!(
+            "发送间隔不能超过{}毫秒",
+            MAX_PRODUCE_INTERVAL
+        )));
+    }
+    let codec = clients.codec(token, &request.message.topic, &db).await?;
+    let (producer, connect) = clients.producer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let job_id = executor.submit(|job_id, signal| {
+        run_produce_job(app, job_id, producer, request, codec, timeout, signal)
+    });
+    info!("produce_batch job submitted: {}", job_id);
+    Ok(CommonResponse::success(job_id))
+}
+
+/// 读取 JSONL 或 CSV 文件，每行发送一条消息，dry-run 时只校验前 N 行
+#[tauri::command]
+pub async fn produce_file(
+    token: &str,
+    request: FileProduceRequest,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<FileProduceReport>> {
+    if request.topic.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let content = tokio::fs::read_to_string(&request.path).await?;
+    let limit = if request.dry_run {
+        Some(request.validate_rows.unwrap_or(10))
+    } else {
+        None
+    };
+    let records = match parse_records(&content, request.format, limit) {
+        Ok(records) => records,
+        Err(e) => return Ok(CommonResponse::error(format!("文件解析失败: {}", e))),
+    };
+
+    let mut report = FileProduceReport {
+        total_rows: records.len(),
+        ..Default::default()
+    };
+    let mut messages = Vec::new();
+    for item in records {
+        match item
+            .record
+            .and_then(|record| map_record(&request.topic, &record, &request.mapping))
+        {
+            Ok(message) => messages.push((item.line, message)),
+            Err(reason) => report.failed_rows.push(FailedRow {
+                line: item.line,
+                reason,
+            }),
+        }
+    }
+    let codec = clients.codec(token, &request.topic, &db).await?;
+    if request.dry_run {
+        // 预览时按 topic 的 key/value 格式编码，提前发现与 schema 不匹配的行
+        for (line, message) in messages {
+            let encoded = [
+                ("key", &message.key, &codec.key),
+                ("value", &message.value, &codec.value),
+            ]
+            .into_iter()
+            .try_for_each(|(field, text, field_codec)| match text {
+                Some(text) => field_codec
+                    .encode(text)
+                    .map(|_| ())
+                    .map_err(|e| format!("{}编码失败: {}", field, e)),
+                None => Ok(()),
+            });
+            match encoded {
+                Ok(_) => report.preview.push(message),
+                Err(reason) => report.failed_rows.push(FailedRow { line, reason }),
+            }
+        }
+        report.success_rows = report.preview.len();
+        report.failed_rows.sort_by_key(|item| item.line);
+        return Ok(CommonResponse::success(report));
+    }
+
+    let (producer, connect) = clients.producer(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let mut pending = JoinSet::new();
+    for (line, message) in messages {
+        if pending.len() >= FILE_PRODUCE_IN_FLIGHT {
+            if let Some(joined) = pending.join_next().await {
+                record_file_delivery(&mut report, joined?);
+            }
+        }
+        let producer = producer.clone();
+        let codec = codec.clone();
+        pending.spawn(async move {
+            (
+                line,
+                send_message(&producer, &message, &codec, timeout).await,
+            )
+        });
+    }
+    while let Some(joined) = pending.join_next().await {
+        record_file_delivery(&mut report, joined?);
+    }
+    report.failed_rows.sort_by_key(|item| item.line);
+    info!(
+        "produce_file path: {}, total: {}, success: {}, failed: {}",
+        request.path,
+        report.total_rows,
+        report.success_rows,
+        report.failed_rows.len()
+    );
+    Ok(CommonResponse::success(report))
+}
+
+fn record_file_delivery(report: &mut FileProduceReport, (line, result): (u64, DeliveryResult)) {
+    match result {
+        DeliveryResult::Delivered { .. } => report.success_rows += 1,
+        DeliveryResult::Failed { msg, .. } => {
+            report.failed_rows.push(FailedRow { line, reason: msg })
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::entity::response::common::CommonResponse;
+use crate::entity::response::registry::{CompatibilityResult, RegisteredSchema, SchemaType};
+use crate::infra::kafka_infra::find_cluster;
+use crate::infra::schema_registry::SchemaRegistryClient;
+use crate::EasyKafkaResult;
+use sea_orm::DatabaseConnection;
+use tauri::State;
+
+const REGISTRY_NOT_CONFIGURED: &str = "集群未配置schema registry地址";
+
+#[tauri::command]
+pub async fn registry_subjects(
+    token: &str,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<Vec<String>>> {
+    let client = match registry_client(token, &db).await? {
+        Some(client) => client,
+        None => return Ok(CommonResponse::error(REGISTRY_NOT_CONFIGURED.to_string())),
+    };
+    Ok(CommonResponse::success(client.subjects().await?))
+}
+
+#[tauri::command]
+pub async fn registry_versions(
+    token: &str,
+    subject: &str,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<Vec<i32>>> {
+    if subject.is_empty() {
+        return Ok(CommonResponse::error("subject不能为空".to_string()));
+    }
+    let client = match registry_client(token, &db).await? {
+        Some(client) => client,
+        None => return Ok(CommonResponse::error(REGISTRY_NOT_CONFIGURED.to_string())),
+    };
+    Ok(CommonResponse::success(client.versions(subject).await?))
+}
+
+/// 查询 subject 的指定版本，版本为空时查询最新版本
+#[tauri::command]
+pub async fn registry_schema(
+    token: &str,
+    subject: &str,
+    version: Option<i32>,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<RegisteredSchema>> {
+    if subject.is_empty() {
+        return Ok(CommonResponse::error("subject不能为空".to_string()));
+    }
+    let client = match registry_client(token, &db).await? {
+        Some(client) => client,
+        None => return Ok(CommonResponse::error(REGISTRY_NOT_CONFIGURED.to_string())),
+    };
+    Ok(CommonResponse::success(
+        client.schema_by_version(subject, version).await?,
+    ))
+}
+
+/// 检查新 schema 与 subject 的指定版本是否兼容，版本为空时与最新版本比较
+#[tauri::command]
+pub async fn registry_check_compatibility(
+    token: &str,
+    subject: &str,
+    version: Option<i32>,
+    schema: &str,
+    schema_type: Option<SchemaType>,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<CompatibilityResult>> {
+    if subject.is_empty() || schema.is_empty() {
+        return Ok(CommonResponse::error("subject和schema不能为空".to_string()));
+    }
+    let client = match registry_client(token, &db).await? {
+        Some(client) => client,
+        None => return Ok(CommonResponse::error(REGISTRY_NOT_CONFIGURED.to_string())),
+    };
+    let result = client
+        .check_compatibility(subject, version, schema, schema_type.unwrap_or_default())
+        .await?;
+    Ok(CommonResponse::success(result))
+}
+
+async fn registry_client(
+    token: &str,
+    db: &DatabaseConnection,
+) -> EasyKafkaResult<Option<SchemaRegistryClient>> {
+    let connect = find_cluster(token, db).await?;
+    SchemaRegistryClient::from_cluster(&connect)
+}
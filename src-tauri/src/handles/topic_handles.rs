@@ -1,11 +1,37 @@
-use crate::config::EasyKafkaConfig;
+use crate::entity::request::topic::{TopicFormat, TruncatePosition};
+use crate::entity::response::topic::{
+    PartitionIncreaseResponse, PartitionTruncateResult, TopicConfigChange, TopicConfigEntry,
+    TopicListResponse, TopicOperationResult, TopicResponse,
+};
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::kafka_infra::{
+    build_record_codec, build_topic_response, create_consumer, fetch_metadata,
+    fetch_offsets_for_timestamp, find_topic_format, is_internal_topic,
+};
+use crate::utils::partitioner::default_partition;
 use crate::{
     entity::{db_entity::topics, response::common::CommonResponse},
-    infra::sql_infra::get_connect,
     EasyKafkaError, EasyKafkaResult,
 };
-use sea_orm::ActiveModelTrait;
+use log::info;
+use rdkafka::admin::{
+    AdminClient, AdminOptions, AlterConfig, ConfigResource, ConfigSource, NewPartitions, NewTopic,
+    ResourceSpecifier, TopicReplication,
+};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::util::Timeout;
+use rdkafka::{Message, Offset, TopicPartitionList};
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Instant;
 use tauri::State;
+use tokio::time::Duration;
 
 #[tauri::command]
 pub async fn load_topic_config_template() -> EasyKafkaResult<String> {
@@ -14,15 +40,831 @@ pub async fn load_topic_config_template() -> EasyKafkaResult<String> {
         .map_err(|e| EasyKafkaError::IOError(e.into()))
 }
 
+#[tauri::command]
+pub async fn topic_list(
+    token: &str,
+    page: i32,
+    limit: i32,
+    search: Option<String>,
+    include_internal: Option<bool>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<TopicListResponse> {
+    info!("topic_list token: {}, search: {:?}", token, search);
+    if page <= 0 || limit <= 0 {
+        return Err(EasyKafkaError::InvalidParam(
+            "page和limit必须大于0".to_string(),
+        ));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let search = search
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty());
+    let include_internal = include_internal.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || -> EasyKafkaResult<TopicListResponse> {
+        let metadata = fetch_metadata(&admin_client, None, timeout)?;
+        let mut topics = metadata
+            .topics()
+            .iter()
+            .filter(|topic| include_internal || !is_internal_topic(topic.name()))
+            .filter(|topic| match &search {
+                Some(search) => topic.name().to_lowercase().contains(search),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        topics.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // 只为当前页的 topic 查询水位
+        let mut result = Vec::new();
+        for topic in topics
+            .iter()
+            .skip((page as usize - 1) * limit as usize)
+            .take(limit as usize)
+        {
+            result.push(build_topic_response(&admin_client, topic, timeout)?);
+        }
+        let mut response = TopicListResponse::default();
+        response.set_current(page);
+        response.set_limit(limit);
+        response.set_total(topics.len() as u64);
+        response.set_list(result);
+        Ok(response)
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn topic_detail(
+    token: &str,
+    topic_name: &str,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<TopicResponse>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let topic_name = topic_name.to_string();
+    tokio::task::spawn_blocking(move || -> EasyKafkaResult<CommonResponse<TopicResponse>> {
+        let metadata = fetch_metadata(&admin_client, Some(&topic_name), timeout)?;
+        match metadata
+            .topics()
+            .iter()
+            .find(|item| item.name() == topic_name)
+        {
+            Some(topic) if topic.error().is_none() => Ok(CommonResponse::success(
+                build_topic_response(&admin_client, topic, timeout)?,
+            )),
+            _ => Ok(CommonResponse::error(format!(
+                "topic[{}]不存在",
+                topic_name
+            ))),
+        }
+    })
+    .await?
+}
+
 #[tauri::command]
 pub async fn create_topic(
+    token: &str,
     topic: topics::Model,
-    config: State<'_, EasyKafkaConfig>,
+    validate_only: Option<bool>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
 ) -> EasyKafkaResult<CommonResponse<String>> {
-    let db_connect = get_connect(&config.database).await?;
-    let res = topics::ActiveModel::from(topic).insert(&db_connect).await;
+    if token.is_empty() {
+        return Ok(CommonResponse::error("token不能为空".to_string()));
+    }
+    let properties = match parse_topic_properties(topic.properties.as_deref()) {
+        Ok(properties) => properties,
+        Err(e) => return Ok(CommonResponse::error(format!("properties格式错误: {}", e))),
+    };
+    let validate_only = validate_only.unwrap_or(false);
+
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let mut new_topic = NewTopic::new(
+        &topic.topic_name,
+        topic.partition,
+        TopicReplication::Fixed(topic.replica),
+    );
+    for (key, value) in properties.iter() {
+        new_topic = new_topic.set(key, value);
+    }
+    let options = AdminOptions::new()
+        .operation_timeout(Some(Duration::from_millis(connect.timeout as u64)))
+        .validate_only(validate_only);
+
+    // 只有 broker 接受了 topic 之后才写入本地数据库
+    for result in admin_client.create_topics(&[new_topic], &options).await? {
+        if let Err((name, code)) = result {
+            info!("create_topic failed, topic: {}, code: {:?}", name, code);
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]创建失败: {}",
+                name, code
+            )));
+        }
+    }
+    if validate_only {
+        return Ok(CommonResponse::success("校验通过".to_string()));
+    }
+
+    let mut active_model = topics::ActiveModel::from(topic);
+    active_model.id = NotSet;
+    active_model.cluster_id = Set(Some(token.to_string()));
+    let res = active_model.insert(db.inner()).await;
     match res {
         Ok(_) => Ok(CommonResponse::success("success".to_string())),
         Err(_) => Ok(CommonResponse::error("create topic failed".to_string())),
     }
 }
+
+/// 删除 topic，内部 topic 和被标记为受保护的 topic 需要 force 才能删除
+#[tauri::command]
+pub async fn delete_topics(
+    token: &str,
+    topic_names: Option<Vec<String>>,
+    pattern: Option<String>,
+    dry_run: Option<bool>,
+    force: Option<bool>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<TopicOperationResult>>> {
+    if token.is_empty() {
+        return Ok(CommonResponse::error("token不能为空".to_string()));
+    }
+    let topic_names = topic_names.unwrap_or_default();
+    let pattern = pattern.filter(|item| !item.trim().is_empty());
+    if topic_names.is_empty() && pattern.is_none() {
+        return Ok(CommonResponse::error("请指定要删除的topic".to_string()));
+    }
+    // 正则需要完整匹配 topic 名称，避免误删
+    let regex = match pattern
+        .as_deref()
+        .map(|item| Regex::new(&format!("^(?:{})$", item)))
+    {
+        Some(Ok(regex)) => Some(regex),
+        Some(Err(e)) => return Ok(CommonResponse::error(format!("正则表达式错误: {}", e))),
+        None => None,
+    };
+    let dry_run = dry_run.unwrap_or(false);
+    let force = force.unwrap_or(false);
+
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let metadata_client = admin_client.clone();
+    let existing = tokio::task::spawn_blocking(move || -> EasyKafkaResult<HashSet<String>> {
+        Ok(fetch_metadata(&metadata_client, None, timeout)?
+            .topics()
+            .iter()
+            .map(|item| item.name().to_string())
+            .collect())
+    })
+    .await??;
+
+    let mut results = Vec::new();
+    let mut selected = Vec::new();
+    for name in topic_names.iter() {
+        if existing.contains(name) {
+            selected.push(name.clone());
+        } else {
+            results.push(TopicOperationResult::error(name, "topic不存在".to_string()));
+        }
+    }
+    if let Some(regex) = regex {
+        let mut matched = existing
+            .iter()
+            .filter(|item| regex.is_match(item) && !selected.contains(item))
+            .cloned()
+            .collect::<Vec<_>>();
+        matched.sort();
+        selected.extend(matched);
+    }
+
+    let protected = topics::Entity::find()
+        .filter(topics::Column::ClusterId.eq(token))
+        .filter(topics::Column::Protected.eq(true))
+        .all(db.inner())
+        .await?
+        .into_iter()
+        .map(|item| item.topic_name)
+        .collect::<HashSet<_>>();
+
+    let mut deletable = Vec::new();
+    for name in selected {
+        if !force && is_internal_topic(&name) {
+            results.push(TopicOperationResult::error(
+                &name,
+                "内部topic，需要强制删除".to_string(),
+            ));
+        } else if !force && protected.contains(&name) {
+            results.push(TopicOperationResult::error(
+                &name,
+                "受保护的topic，需要强制删除".to_string(),
+            ));
+        } else {
+            deletable.push(name);
+        }
+    }
+
+    if dry_run || deletable.is_empty() {
+        results.extend(
+            deletable
+                .iter()
+                .map(|name| TopicOperationResult::success(name, "将被删除")),
+        );
+        return Ok(CommonResponse::success(results));
+    }
+
+    info!("delete_topics token: {}, topics: {:?}", token, deletable);
+    let options = AdminOptions::new().operation_timeout(Some(timeout));
+    let names = deletable
+        .iter()
+        .map(|item| item.as_str())
+        .collect::<Vec<_>>();
+    let mut deleted = Vec::new();
+    for result in admin_client.delete_topics(&names, &options).await? {
+        match result {
+            Ok(name) => {
+                results.push(TopicOperationResult::success(&name, "删除成功"));
+                deleted.push(name);
+            }
+            Err((name, code)) => results.push(TopicOperationResult::error(
+                &name,
+                format!("删除失败: {}", code),
+            )),
+        }
+    }
+    if !deleted.is_empty() {
+        for name in deleted.iter() {
+            clients.invalidate_codec(token, name);
+        }
+        topics::Entity::delete_many()
+            .filter(topics::Column::ClusterId.eq(token))
+            .filter(topics::Column::TopicName.is_in(deleted))
+            .exec(db.inner())
+            .await?;
+    }
+    Ok(CommonResponse::success(results))
+}
+
+/// 标记或取消标记受保护的 topic，本地没有记录时根据集群元数据补充
+#[tauri::command]
+pub async fn topic_protect(
+    token: &str,
+    topic_name: &str,
+    protected: bool,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if token.is_empty() || topic_name.is_empty() {
+        return Ok(CommonResponse::error(
+            "token和topic名称不能为空".to_string(),
+        ));
+    }
+    let model = match find_or_register_topic(token, topic_name, &db, &clients).await? {
+        Some(model) => model,
+        None => {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]不存在",
+                topic_name
+            )))
+        }
+    };
+    let mut active_model = topics::ActiveModel::from(model);
+    active_model.protected = Set(protected);
+    active_model.update(db.inner()).await?;
+    Ok(CommonResponse::success("操作成功".to_string()))
+}
+
+/// 查询 topic 的 key/value 格式，未配置时为文本
+#[tauri::command]
+pub async fn topic_format(
+    token: &str,
+    topic_name: &str,
+    db: State<'_, DatabaseConnection>,
+) -> EasyKafkaResult<CommonResponse<TopicFormat>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let format = find_topic_format(token, topic_name, &db).await?;
+    Ok(CommonResponse::success(format))
+}
+
+/// 保存 topic 的 key/value 格式，保存前加载 schema 校验配置是否可用
+#[tauri::command]
+pub async fn update_topic_format(
+    token: &str,
+    topic_name: &str,
+    format: TopicFormat,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<String>> {
+    if token.is_empty() || topic_name.is_empty() {
+        return Ok(CommonResponse::error(
+            "token和topic名称不能为空".to_string(),
+        ));
+    }
+    let connect = clients.cluster(token, &db).await?;
+    if let Err(e) = build_record_codec(&format, &connect).await {
+        return Ok(CommonResponse::error(e.to_string()));
+    }
+    let model = match find_or_register_topic(token, topic_name, &db, &clients).await? {
+        Some(model) => model,
+        None => {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]不存在",
+                topic_name
+            )))
+        }
+    };
+    let mut active_model = topics::ActiveModel::from(model);
+    active_model.key_format = Set(Some(serde_json::to_string(&format.key)?));
+    active_model.value_format = Set(Some(serde_json::to_string(&format.value)?));
+    active_model.update(db.inner()).await?;
+    clients.invalidate_codec(token, topic_name);
+    info!(
+        "update_topic_format topic: {}, format: {:?}",
+        topic_name, format
+    );
+    Ok(CommonResponse::success("操作成功".to_string()))
+}
+
+/// 查找 topic 的本地记录，没有记录时根据集群元数据补充，topic 不存在时返回 None
+async fn find_or_register_topic(
+    token: &str,
+    topic_name: &str,
+    db: &DatabaseConnection,
+    clients: &ClientRegistry,
+) -> EasyKafkaResult<Option<topics::Model>> {
+    let find_result = topics::Entity::find()
+        .filter(topics::Column::ClusterId.eq(token))
+        .filter(topics::Column::TopicName.eq(topic_name))
+        .one(db)
+        .await?;
+    if find_result.is_some() {
+        return Ok(find_result);
+    }
+    let (admin_client, connect) = clients.admin(token, db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let name = topic_name.to_string();
+    let found = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Option<(i32, i32)>> {
+        let metadata = fetch_metadata(&admin_client, Some(&name), timeout)?;
+        Ok(metadata
+            .topics()
+            .iter()
+            .find(|item| item.name() == name && item.error().is_none())
+            .map(|topic| {
+                (
+                    topic.partitions().len() as i32,
+                    topic
+                        .partitions()
+                        .iter()
+                        .map(|item| item.replicas().len())
+                        .max()
+                        .unwrap_or(0) as i32,
+                )
+            }))
+    })
+    .await??;
+    let (partition, replica) = match found {
+        Some(found) => found,
+        None => return Ok(None),
+    };
+    let model = topics::ActiveModel {
+        id: NotSet,
+        topic_name: Set(topic_name.to_string()),
+        partition: Set(partition),
+        replica: Set(replica),
+        properties: Set(None),
+        cluster_id: Set(Some(token.to_string())),
+        protected: Set(false),
+        key_format: Set(None),
+        value_format: Set(None),
+    }
+    .insert(db)
+    .await?;
+    Ok(Some(model))
+}
+
+#[tauri::command]
+pub async fn describe_topic_config(
+    token: &str,
+    topic_name: &str,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<TopicConfigEntry>>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let resource = match describe_config_resource(&admin_client, topic_name, timeout).await? {
+        Ok(resource) => resource,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+    let mut entries = resource
+        .entries
+        .into_iter()
+        .map(|entry| TopicConfigEntry {
+            source: config_source_name(&entry.source).to_string(),
+            name: entry.name,
+            value: entry.value,
+            is_default: entry.is_default,
+            is_read_only: entry.is_read_only,
+            is_sensitive: entry.is_sensitive,
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(CommonResponse::success(entries))
+}
+
+/// 修改 topic 配置，changes 中值为 null 的配置项会恢复为默认值。
+/// AlterConfigs 会用请求中的配置整体覆盖 topic 的配置，所以这里先读取当前 topic 级别的配置，
+/// 合并修改后再提交，保证未修改的配置项保持原值
+#[tauri::command]
+pub async fn alter_topic_config(
+    token: &str,
+    topic_name: &str,
+    changes: HashMap<String, Option<String>>,
+    validate_only: Option<bool>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<TopicConfigChange>>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let resource = match describe_config_resource(&admin_client, topic_name, timeout).await? {
+        Ok(resource) => resource,
+        Err(msg) => return Ok(CommonResponse::error(msg)),
+    };
+
+    let mut current = BTreeMap::new();
+    for entry in resource.entries.iter() {
+        if entry.source != ConfigSource::DynamicTopic {
+            continue;
+        }
+        match &entry.value {
+            Some(value) => {
+                current.insert(entry.name.clone(), value.clone());
+            }
+            // 敏感配置读不到原值，无法在整体覆盖时保留
+            None if !changes.contains_key(&entry.name) => {
+                return Ok(CommonResponse::error(format!(
+                    "配置项[{}]为敏感配置，无法读取原值，请在本次修改中一并提供",
+                    entry.name
+                )));
+            }
+            None => {}
+        }
+    }
+    for (name, _) in changes.iter() {
+        if let Some(entry) = resource.get(name) {
+            if entry.is_read_only {
+                return Ok(CommonResponse::error(format!("配置项[{}]为只读配置", name)));
+            }
+        }
+    }
+
+    let mut target = current.clone();
+    for (name, value) in changes.iter() {
+        match value {
+            Some(value) => target.insert(name.clone(), value.clone()),
+            None => target.remove(name),
+        };
+    }
+    let mut diff = Vec::new();
+    let names = current
+        .keys()
+        .chain(target.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        let old_value = current.get(&name).cloned();
+        let new_value = target.get(&name).cloned();
+        if old_value != new_value {
+            diff.push(TopicConfigChange {
+                name,
+                old_value,
+                new_value,
+            });
+        }
+    }
+    if diff.is_empty() {
+        return Ok(CommonResponse::success(diff));
+    }
+
+    let mut alter_config = AlterConfig::new(ResourceSpecifier::Topic(topic_name));
+    for (name, value) in target.iter() {
+        alter_config = alter_config.set(name, value);
+    }
+    let options = AdminOptions::new()
+        .operation_timeout(Some(timeout))
+        .validate_only(validate_only.unwrap_or(false));
+    for result in admin_client
+        .alter_configs(&[alter_config], &options)
+        .await?
+    {
+        if let Err((_, code)) = result {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]配置修改失败: {}",
+                topic_name, code
+            )));
+        }
+    }
+    info!(
+        "alter_topic_config topic: {}, changes: {:?}",
+        topic_name, diff
+    );
+    Ok(CommonResponse::success(diff))
+}
+
+/// 增加 topic 的分区数，提交前采样最近的 key，评估默认分区器下会被重新映射到其他分区的比例
+#[tauri::command]
+pub async fn increase_partitions(
+    token: &str,
+    topic_name: &str,
+    partition_count: usize,
+    sample_size: Option<i64>,
+    dry_run: Option<bool>,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<PartitionIncreaseResponse>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let metadata_client = admin_client.clone();
+    let metadata_topic = topic_name.to_string();
+    let partitions = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Option<Vec<i32>>> {
+        let metadata = fetch_metadata(&metadata_client, Some(&metadata_topic), timeout)?;
+        Ok(metadata
+            .topics()
+            .iter()
+            .find(|item| item.name() == metadata_topic && item.error().is_none())
+            .map(|topic| topic.partitions().iter().map(|item| item.id()).collect()))
+    })
+    .await??;
+    let partitions = match partitions {
+        Some(partitions) => partitions,
+        None => {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]不存在",
+                topic_name
+            )))
+        }
+    };
+    let current_partition_count = partitions.len();
+    if partition_count <= current_partition_count {
+        return Ok(CommonResponse::error(format!(
+            "分区数只能增加，当前分区数: {}",
+            current_partition_count
+        )));
+    }
+
+    let sample_topic = topic_name.to_string();
+    let sample_size = sample_size.unwrap_or(1000).max(1);
+    let keys = tokio::task::spawn_blocking(move || {
+        let consumer = create_consumer(&connect)?;
+        sample_recent_keys(consumer, &sample_topic, &partitions, sample_size, timeout)
+    })
+    .await??;
+    let remapped_keys = keys
+        .iter()
+        .filter(|key| {
+            default_partition(key, current_partition_count as i32)
+                != default_partition(key, partition_count as i32)
+        })
+        .count();
+
+    let mut response = PartitionIncreaseResponse {
+        topic_name: topic_name.to_string(),
+        current_partition_count,
+        new_partition_count: partition_count,
+        sampled_keys: keys.len(),
+        remapped_keys,
+        remapped_ratio: if keys.is_empty() {
+            0.0
+        } else {
+            remapped_keys as f64 / keys.len() as f64
+        },
+        applied: false,
+    };
+    if dry_run.unwrap_or(false) {
+        return Ok(CommonResponse::success(response));
+    }
+
+    let options = AdminOptions::new().operation_timeout(Some(timeout));
+    let new_partitions = NewPartitions::new(topic_name, partition_count);
+    for result in admin_client
+        .create_partitions(&[new_partitions], &options)
+        .await?
+    {
+        if let Err((name, code)) = result {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]增加分区失败: {}",
+                name, code
+            )));
+        }
+    }
+    info!(
+        "increase_partitions topic: {}, {} -> {}",
+        topic_name, current_partition_count, partition_count
+    );
+    response.applied = true;
+    Ok(CommonResponse::success(response))
+}
+
+/// 通过 DeleteRecords 截断 topic 数据，partitions 为空时截断所有分区
+#[tauri::command]
+pub async fn delete_records(
+    token: &str,
+    topic_name: &str,
+    partitions: Option<Vec<i32>>,
+    position: TruncatePosition,
+    db: State<'_, DatabaseConnection>,
+    clients: State<'_, ClientRegistry>,
+) -> EasyKafkaResult<CommonResponse<Vec<PartitionTruncateResult>>> {
+    if topic_name.is_empty() {
+        return Ok(CommonResponse::error("topic名称不能为空".to_string()));
+    }
+    let (admin_client, connect) = clients.admin(token, &db).await?;
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let metadata_client = admin_client.clone();
+    let metadata_topic = topic_name.to_string();
+    let existing = tokio::task::spawn_blocking(move || -> EasyKafkaResult<Option<Vec<i32>>> {
+        let metadata = fetch_metadata(&metadata_client, Some(&metadata_topic), timeout)?;
+        Ok(metadata
+            .topics()
+            .iter()
+            .find(|item| item.name() == metadata_topic && item.error().is_none())
+            .map(|topic| topic.partitions().iter().map(|item| item.id()).collect()))
+    })
+    .await??;
+    let existing = match existing {
+        Some(existing) => existing,
+        None => {
+            return Ok(CommonResponse::error(format!(
+                "topic[{}]不存在",
+                topic_name
+            )))
+        }
+    };
+    let partitions = match partitions.filter(|item| !item.is_empty()) {
+        Some(partitions) => {
+            if let Some(partition) = partitions.iter().find(|item| !existing.contains(item)) {
+                return Ok(CommonResponse::error(format!("分区[{}]不存在", partition)));
+            }
+            partitions
+        }
+        None => existing,
+    };
+
+    let offsets = match position {
+        TruncatePosition::HighWatermark => partitions
+            .iter()
+            .map(|partition| (*partition, Offset::End))
+            .collect::<HashMap<_, _>>(),
+        TruncatePosition::Offset { offset } => partitions
+            .iter()
+            .map(|partition| (*partition, Offset::Offset(offset)))
+            .collect(),
+        TruncatePosition::Timestamp { timestamp } => {
+            let (consumer, _) = clients.consumer(token, &db).await?;
+            let offsets_topic = topic_name.to_string();
+            tokio::task::spawn_blocking(move || {
+                fetch_offsets_for_timestamp(
+                    &consumer,
+                    &offsets_topic,
+                    &partitions,
+                    timestamp,
+                    timeout,
+                )
+            })
+            .await??
+        }
+    };
+    let mut delete_offsets = TopicPartitionList::new();
+    for (partition, offset) in offsets.iter() {
+        delete_offsets.add_partition_offset(topic_name, *partition, *offset)?;
+    }
+
+    info!(
+        "delete_records topic: {}, offsets: {:?}",
+        topic_name, offsets
+    );
+    let options = AdminOptions::new().operation_timeout(Some(timeout));
+    let result = admin_client
+        .delete_records(&delete_offsets, &options)
+        .await?;
+    let mut response = result
+        .elements_for_topic(topic_name)
+        .iter()
+        .map(|item| match item.error() {
+            Ok(_) => PartitionTruncateResult {
+                partition: item.partition(),
+                low_watermark: item.offset().to_raw(),
+                success: true,
+                msg: "截断成功".to_string(),
+            },
+            Err(e) => PartitionTruncateResult {
+                partition: item.partition(),
+                low_watermark: None,
+                success: false,
+                msg: format!("截断失败: {}", e),
+            },
+        })
+        .collect::<Vec<_>>();
+    response.sort_by_key(|item| item.partition);
+    Ok(CommonResponse::success(response))
+}
+
+/// 从每个分区末尾读取最多 sample_size 条消息，返回去重后的 key
+fn sample_recent_keys(
+    consumer: BaseConsumer,
+    topic_name: &str,
+    partitions: &[i32],
+    sample_size: i64,
+    timeout: Duration,
+) -> EasyKafkaResult<HashSet<Vec<u8>>> {
+    let mut assignment = TopicPartitionList::new();
+    let mut remaining = 0;
+    for partition in partitions {
+        let (low, high) = consumer.fetch_watermarks(topic_name, *partition, timeout)?;
+        let start = (high - sample_size).max(low);
+        if start < high {
+            assignment.add_partition_offset(topic_name, *partition, Offset::Offset(start))?;
+            remaining += high - start;
+        }
+    }
+    let mut keys = HashSet::new();
+    if remaining == 0 {
+        return Ok(keys);
+    }
+    consumer.assign(&assignment)?;
+
+    // 事务标记和压缩会导致实际消息数少于水位差，以超时时间兜底
+    let deadline = Instant::now() + timeout;
+    while remaining > 0 && Instant::now() < deadline {
+        match consumer.poll(Timeout::from(Duration::from_millis(200))) {
+            Some(Ok(message)) => {
+                remaining -= 1;
+                if let Some(key) = message.key() {
+                    keys.insert(key.to_vec());
+                }
+            }
+            Some(Err(e)) => return Err(e.into()),
+            None => {}
+        }
+    }
+    Ok(keys)
+}
+
+/// 查询 topic 的配置，topic 级别的错误以提示信息返回
+async fn describe_config_resource(
+    admin_client: &AdminClient<DefaultClientContext>,
+    topic_name: &str,
+    timeout: Duration,
+) -> EasyKafkaResult<Result<ConfigResource, String>> {
+    let options = AdminOptions::new().request_timeout(Some(timeout));
+    let results = admin_client
+        .describe_configs(&[ResourceSpecifier::Topic(topic_name)], &options)
+        .await?;
+    match results.into_iter().next() {
+        Some(Ok(resource)) => Ok(Ok(resource)),
+        Some(Err(code)) => Ok(Err(format!("topic[{}]配置查询失败: {}", topic_name, code))),
+        None => Ok(Err(format!("topic[{}]配置查询失败", topic_name))),
+    }
+}
+
+fn config_source_name(source: &ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::Unknown => "UNKNOWN",
+        ConfigSource::DynamicTopic => "DYNAMIC_TOPIC_CONFIG",
+        ConfigSource::DynamicBroker => "DYNAMIC_BROKER_CONFIG",
+        ConfigSource::DynamicDefaultBroker => "DYNAMIC_DEFAULT_BROKER_CONFIG",
+        ConfigSource::StaticBroker => "STATIC_BROKER_CONFIG",
+        ConfigSource::Default => "DEFAULT_CONFIG",
+    }
+}
+
+/// 将前端传入的 properties JSON 转换为 topic 配置项，非字符串的值按 JSON 文本处理
+fn parse_topic_properties(properties: Option<&str>) -> EasyKafkaResult<HashMap<String, String>> {
+    let content = match properties {
+        Some(content) if !content.trim().is_empty() => content,
+        _ => return Ok(HashMap::new()),
+    };
+    let values: HashMap<String, serde_json::Value> = serde_json::from_str(content)?;
+    Ok(values
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => (key, value),
+            other => (key, other.to_string()),
+        })
+        .collect())
+}
@@ -0,0 +1,249 @@
+use crate::entity::db_entity::cluster;
+use crate::infra::kafka_infra::{
+    create_admin_client, create_consumer, create_producer, find_cluster, load_record_codec,
+};
+use crate::utils::codec::RecordCodec;
+use crate::EasyKafkaResult;
+use log::info;
+use rdkafka::admin::AdminClient;
+use rdkafka::client::DefaultClientContext;
+use rdkafka::consumer::BaseConsumer;
+use rdkafka::producer::FutureProducer;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{self, Duration, Instant};
+
+/// 检查空闲客户端的最大间隔
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 按集群缓存的 Kafka 客户端和 topic 编解码器，在第一次使用时创建，空闲超过 idle_ttl 后关闭。
+/// 集群配置修改或删除后需要调用 invalidate，topic 格式修改后需要调用 invalidate_codec，
+/// 下次使用时按新的配置重新创建
+#[derive(Clone)]
+pub struct ClientRegistry {
+    state: Arc<Mutex<RegistryState>>,
+    idle_ttl: Duration,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    clusters: HashMap<String, ClusterClients>,
+    /// 集群缓存的版本，每次 invalidate 时递增。读取数据库和创建客户端都在锁外进行，
+    /// 写入缓存前版本发生变化说明期间配置被修改过，结果只返回给本次调用，不写入缓存
+    generations: HashMap<String, u64>,
+}
+
+impl RegistryState {
+    fn generation(&self, token: &str) -> u64 {
+        self.generations.get(token).copied().unwrap_or_default()
+    }
+
+    /// 版本与读取时一致时返回集群的缓存
+    fn current(&mut self, token: &str, generation: u64) -> Option<&mut ClusterClients> {
+        if self.generation(token) != generation {
+            return None;
+        }
+        self.clusters.get_mut(token)
+    }
+}
+
+struct ClusterClients {
+    connect: cluster::Model,
+    admin: Option<Arc<AdminClient<DefaultClientContext>>>,
+    producer: Option<FutureProducer>,
+    consumer: Option<Arc<BaseConsumer>>,
+    codecs: HashMap<String, Arc<RecordCodec>>,
+    last_used: Instant,
+}
+
+impl ClientRegistry {
+    pub fn new(idle_ttl: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RegistryState::default())),
+            idle_ttl: idle_ttl.max(Duration::from_secs(1)),
+        }
+    }
+
+    /// 集群配置，缓存中没有时从数据库读取
+    pub async fn cluster(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<cluster::Model> {
+        Ok(self.entry(token, db).await?.0)
+    }
+
+    pub async fn admin(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<(Arc<AdminClient<DefaultClientContext>>, cluster::Model)> {
+        self.client(
+            token,
+            db,
+            |clients| &mut clients.admin,
+            |connect| Ok(Arc::new(create_admin_client(connect)?)),
+        )
+        .await
+    }
+
+    pub async fn producer(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<(FutureProducer, cluster::Model)> {
+        self.client(token, db, |clients| &mut clients.producer, create_producer)
+            .await
+    }
+
+    /// 共享的消费者，只用于查询元数据、水位和消费者组，不能 assign 分区读取消息。
+    /// 读取消息需要通过 create_consumer 创建独立的消费者
+    pub async fn consumer(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<(Arc<BaseConsumer>, cluster::Model)> {
+        self.client(
+            token,
+            db,
+            |clients| &mut clients.consumer,
+            |connect| Ok(Arc::new(create_consumer(connect)?)),
+        )
+        .await
+    }
+
+    /// topic 的 key/value 编解码器，按 topic 保存的格式创建后缓存，避免每次请求重新解析 schema
+    pub async fn codec(
+        &self,
+        token: &str,
+        topic_name: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<Arc<RecordCodec>> {
+        let (_, generation) = self.entry(token, db).await?;
+        if let Some(codec) = self
+            .state
+            .lock()
+            .unwrap()
+            .current(token, generation)
+            .and_then(|clients| clients.codecs.get(topic_name).cloned())
+        {
+            return Ok(codec);
+        }
+        let codec = Arc::new(load_record_codec(token, topic_name, db).await?);
+        let mut state = self.state.lock().unwrap();
+        match state.current(token, generation) {
+            Some(clients) => Ok(clients
+                .codecs
+                .entry(topic_name.to_string())
+                .or_insert(codec)
+                .clone()),
+            None => Ok(codec),
+        }
+    }
+
+    /// 丢弃 topic 缓存的编解码器
+    pub fn invalidate_codec(&self, token: &str, topic_name: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.generations.entry(token.to_string()).or_default() += 1;
+        if let Some(clients) = state.clusters.get_mut(token) {
+            clients.codecs.remove(topic_name);
+        }
+    }
+
+    /// 丢弃集群的客户端，正在使用的客户端在最后一个引用释放后关闭
+    pub fn invalidate(&self, token: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.generations.entry(token.to_string()).or_default() += 1;
+        if state.clusters.remove(token).is_some() {
+            info!("invalidate clients for cluster: {}", token);
+        }
+    }
+
+    /// 关闭空闲超过 idle_ttl 的客户端
+    pub fn evict_idle(&self) {
+        let now = Instant::now();
+        self.state
+            .lock()
+            .unwrap()
+            .clusters
+            .retain(|token, clients| {
+                let idle = now.duration_since(clients.last_used) >= self.idle_ttl;
+                if idle {
+                    info!("close idle clients for cluster: {}", token);
+                }
+                !idle
+            });
+    }
+
+    /// 启动后台任务定期关闭空闲的客户端
+    pub fn start_sweeper(&self) {
+        let registry = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = time::interval(SWEEP_INTERVAL.min(registry.idle_ttl));
+            loop {
+                ticker.tick().await;
+                registry.evict_idle();
+            }
+        });
+    }
+
+    /// 返回集群配置和读取时的缓存版本，缓存中没有时从数据库读取
+    async fn entry(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> EasyKafkaResult<(cluster::Model, u64)> {
+        let generation = {
+            let mut state = self.state.lock().unwrap();
+            let generation = state.generation(token);
+            if let Some(clients) = state.clusters.get_mut(token) {
+                clients.last_used = Instant::now();
+                return Ok((clients.connect.clone(), generation));
+            }
+            generation
+        };
+        let connect = find_cluster(token, db).await?;
+        let mut state = self.state.lock().unwrap();
+        if state.generation(token) == generation {
+            state
+                .clusters
+                .entry(token.to_string())
+                .or_insert_with(|| ClusterClients {
+                    connect: connect.clone(),
+                    admin: None,
+                    producer: None,
+                    consumer: None,
+                    codecs: HashMap::new(),
+                    last_used: Instant::now(),
+                });
+        }
+        Ok((connect, generation))
+    }
+
+    /// 返回缓存的客户端，没有时在锁外创建，版本没有变化时写入缓存
+    async fn client<C: Clone>(
+        &self,
+        token: &str,
+        db: &DatabaseConnection,
+        slot: impl Fn(&mut ClusterClients) -> &mut Option<C>,
+        create: impl FnOnce(&cluster::Model) -> EasyKafkaResult<C>,
+    ) -> EasyKafkaResult<(C, cluster::Model)> {
+        let (connect, generation) = self.entry(token, db).await?;
+        if let Some(client) = self
+            .state
+            .lock()
+            .unwrap()
+            .current(token, generation)
+            .and_then(|clients| slot(clients).clone())
+        {
+            return Ok((client, connect));
+        }
+        let client = create(&connect)?;
+        let mut state = self.state.lock().unwrap();
+        match state.current(token, generation) {
+            Some(clients) => Ok((slot(clients).get_or_insert(client).clone(), connect)),
+            None => Ok((client, connect)),
+        }
+    }
+}
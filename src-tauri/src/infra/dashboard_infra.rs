@@ -0,0 +1,239 @@
+use crate::entity::db_entity::cluster;
+use crate::entity::response::consumer_group::ConsumerGroupResponse;
+use crate::entity::response::dashboard::{
+    DashboardStatistics, UnreachableGroup, UnreachablePartition,
+};
+use crate::infra::group_infra::{
+    committed_offsets, fetch_group_list, is_live_group, offsets_assignment,
+};
+use crate::EasyKafkaResult;
+use log::warn;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::util::Timeout;
+use rdkafka::TopicPartitionList;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::{self, Duration, Instant};
+
+/// 仪表盘同时执行的最大 rdkafka 查询数
+const DASHBOARD_PARALLELISM: usize = 8;
+/// 截止时间前没有完成的查询返回的错误
+const UNFINISHED: &str = "未在截止时间内完成";
+
+/// 集群的 broker、分区和消费者组，查询水位和位移前一次性获取
+struct ClusterLayout {
+    broker_count: usize,
+    topic_count: usize,
+    partitions: Vec<(String, i32)>,
+    assignment: TopicPartitionList,
+    groups: Vec<ConsumerGroupResponse>,
+}
+
+/// 在截止时间内并行查询分区水位和消费者组位移，查询失败或超时的分区和消费者组不计入统计，
+/// 放在结果的 unreachable 列表中
+pub async fn collect_statistics(
+    consumer: Arc<BaseConsumer>,
+    connect: cluster::Model,
+    deadline: Instant,
+    top: usize,
+) -> EasyKafkaResult<DashboardStatistics> {
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let layout_consumer = consumer.clone();
+    let layout_timeout = remaining(deadline, timeout);
+    let layout =
+        tokio::task::spawn_blocking(move || fetch_layout(&layout_consumer, layout_timeout))
+            .await??;
+
+    let mut result = DashboardStatistics::default();
+    result.set_broker_count(layout.broker_count);
+    result.set_topic_count(layout.topic_count);
+    result.set_partition_count(layout.partitions.len());
+
+    // 分区水位
+    let group_consumer = consumer.clone();
+    let watermarks = run_parallel(
+        layout.partitions,
+        deadline,
+        timeout,
+        move |(topic, partition), timeout| {
+            Ok(consumer.fetch_watermarks(topic, *partition, Timeout::from(timeout))?)
+        },
+    )
+    .await;
+    let mut highs = HashMap::new();
+    let mut message_count = 0;
+    let mut unreachable_partitions = Vec::new();
+    for ((topic, partition), watermark) in watermarks {
+        match watermark {
+            Ok((low, high)) => {
+                message_count += (high - low).max(0) as usize;
+                highs.insert((topic, partition), high);
+            }
+            Err(msg) => unreachable_partitions.push(UnreachablePartition {
+                topic,
+                partition,
+                msg,
+            }),
+        }
+    }
+    unreachable_partitions.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+    result.set_message_count(message_count);
+    result.set_unreachable_partitions(unreachable_partitions);
+
+    // 消费者组 lag，水位查询失败的分区不计入
+    let assignment = layout.assignment;
+    let offsets = run_parallel(
+        layout.groups,
+        deadline,
+        timeout,
+        move |group: &ConsumerGroupResponse, timeout| {
+            committed_offsets(
+                &group_consumer,
+                &group.group_id,
+                assignment.clone(),
+                timeout,
+            )
+        },
+    )
+    .await;
+    let mut groups = Vec::new();
+    let mut unreachable_groups = Vec::new();
+    for (mut group, committed) in offsets {
+        match committed {
+            Ok(committed) => {
+                group.partitions = committed.len();
+                group.total_lag = committed
+                    .iter()
+                    .filter_map(|(key, offset)| highs.get(key).map(|high| (high - offset).max(0)))
+                    .sum();
+                groups.push(group);
+            }
+            Err(msg) => unreachable_groups.push(UnreachableGroup {
+                group_id: group.group_id,
+                msg,
+            }),
+        }
+    }
+    unreachable_groups.sort_by(|a, b| a.group_id.cmp(&b.group_id));
+    result.set_unreachable_groups(unreachable_groups);
+    result.set_lag_count(groups.iter().map(|item| item.total_lag).sum::<i64>() as usize);
+    groups.retain(|item| item.total_lag > 0);
+    groups.sort_by(|a, b| {
+        b.total_lag
+            .cmp(&a.total_lag)
+            .then_with(|| a.group_id.cmp(&b.group_id))
+    });
+    groups.truncate(top);
+    result.set_top_lag_groups(groups);
+    Ok(result)
+}
+
+fn fetch_layout(consumer: &BaseConsumer, timeout: Duration) -> EasyKafkaResult<ClusterLayout> {
+    let metadata = consumer.fetch_metadata(None, Timeout::from(timeout))?;
+    let partitions = metadata
+        .topics()
+        .iter()
+        .flat_map(|topic| {
+            topic
+                .partitions()
+                .iter()
+                .map(move |partition| (topic.name().to_string(), partition.id()))
+        })
+        .collect();
+    let group_list = fetch_group_list(consumer, None, timeout)?;
+    let groups = group_list
+        .groups()
+        .iter()
+        .filter(|group| is_live_group(group))
+        .map(|group| ConsumerGroupResponse {
+            group_id: group.name().to_string(),
+            state: group.state().to_string(),
+            protocol_type: group.protocol_type().to_string(),
+            members: group.members().len(),
+            partitions: 0,
+            total_lag: 0,
+        })
+        .collect();
+    Ok(ClusterLayout {
+        broker_count: metadata.brokers().len(),
+        topic_count: metadata.topics().len(),
+        partitions,
+        assignment: offsets_assignment(&metadata),
+        groups,
+    })
+}
+
+/// 在阻塞线程池中并行执行查询，同时执行的查询数不超过 DASHBOARD_PARALLELISM。
+/// 每次查询的超时不超过截止时间，截止时间到达后不再等待未完成的查询，直接返回未完成。
+/// 还没有开始的查询会被取消，已经在阻塞线程中执行的查询无法中断，会在各自剩余的超时内结束，结果被丢弃
+async fn run_parallel<K, T, F>(
+    items: Vec<K>,
+    deadline: Instant,
+    timeout: Duration,
+    query: F,
+) -> Vec<(K, Result<T, String>)>
+where
+    K: Clone + Send + 'static,
+    T: Send + 'static,
+    F: Fn(&K, Duration) -> EasyKafkaResult<T> + Send + Sync + 'static,
+{
+    let query = Arc::new(query);
+    let semaphore = Arc::new(Semaphore::new(DASHBOARD_PARALLELISM));
+    let mut pending = HashMap::new();
+    let mut tasks = JoinSet::new();
+    for (index, item) in items.into_iter().enumerate() {
+        pending.insert(index, item.clone());
+        let query = query.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => {
+                    let timeout = remaining(deadline, timeout);
+                    if timeout.is_zero() {
+                        Err(UNFINISHED.to_string())
+                    } else {
+                        match tokio::task::spawn_blocking(move || query(&item, timeout)).await {
+                            Ok(Ok(value)) => Ok(value),
+                            Ok(Err(e)) => Err(e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        }
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            (index, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    loop {
+        match time::timeout_at(deadline, tasks.join_next()).await {
+            Ok(Some(Ok((index, result)))) => {
+                if let Some(item) = pending.remove(&index) {
+                    results.push((item, result));
+                }
+            }
+            Ok(Some(Err(e))) => warn!("dashboard query task failed: {}", e),
+            Ok(None) => break,
+            Err(_) => {
+                tasks.abort_all();
+                break;
+            }
+        }
+    }
+    results.extend(
+        pending
+            .into_values()
+            .map(|item| (item, Err(UNFINISHED.to_string()))),
+    );
+    results
+}
+
+/// 距离截止时间的剩余时间，不超过单次查询的超时
+fn remaining(deadline: Instant, timeout: Duration) -> Duration {
+    deadline
+        .saturating_duration_since(Instant::now())
+        .min(timeout)
+}
@@ -1 +1,705 @@
+use crate::entity::db_entity::cluster;
+use crate::entity::request::consumer::SearchTopicRequest;
+use crate::entity::request::producer::BatchProduceRequest;
+use crate::entity::response::consumer::{
+    ConsumerRecord, PartitionSearchProgress, SearchEvent, TailEvent,
+};
+use crate::entity::response::producer::{DeliveryResult, ProduceJobProgress};
+use crate::infra::kafka_infra::{
+    create_consumer, fetch_offsets_for_timestamp, send_message, to_consumer_record,
+};
+use crate::utils::codec::RecordCodec;
+use crate::utils::message_filter::MessageFilter;
+use crate::EasyKafkaResult;
+use log::{info, warn};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::producer::FutureProducer;
+use rdkafka::util::Timeout;
+use rdkafka::Message;
+use rdkafka::{Offset, TopicPartitionList};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::{JoinError, JoinSet};
+use tokio::time::{self, Duration, Instant, MissedTickBehavior};
+use uuid::Uuid;
 
+pub const PRODUCE_JOB_PROGRESS_EVENT: &str = "produce-job-progress";
+
+/// 批量发送时最多同时等待投递结果的消息数，达到上限后先等待已发送的消息完成
+const PRODUCE_JOB_IN_FLIGHT: usize = 1000;
+/// 延迟直方图中精确记录的毫秒数，更大的延迟按 2 的幂分段
+const LATENCY_EXACT_MILLIS: u64 = 128;
+/// 延迟直方图每个分段均分的桶数，分段内的误差不超过 1/64
+const LATENCY_SUB_BUCKETS: u64 = 64;
+
+/// 实时消费时消费线程和推送任务之间的缓冲大小，缓冲满时消费线程会阻塞，不再拉取新消息
+const TAIL_BUFFER_SIZE: usize = 1000;
+/// 每次推送给前端的最大消息数
+const TAIL_BATCH_SIZE: usize = 200;
+/// 搜索任务同时读取的最大分区数
+const SEARCH_PARALLELISM: usize = 4;
+/// 搜索线程每读取多少条消息更新一次分区进度
+const SEARCH_PROGRESS_STEP: u64 = 500;
+
+/// 后台任务执行器，任务在 tokio 运行时中执行，通过 job id 取消
+#[derive(Default)]
+pub struct JobExecutor {
+    jobs: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+}
+
+impl JobExecutor {
+    pub fn submit<F, Fut>(&self, job: F) -> String
+    where
+        F: FnOnce(String, CancelSignal) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let job_id = Uuid::new_v4().to_string();
+        let (sender, receiver) = watch::channel(false);
+        self.jobs.lock().unwrap().insert(job_id.clone(), sender);
+
+        let jobs = self.jobs.clone();
+        let future = job(job_id.clone(), CancelSignal(receiver));
+        let finished_id = job_id.clone();
+        tauri::async_runtime::spawn(async move {
+            future.await;
+            jobs.lock().unwrap().remove(&finished_id);
+        });
+        job_id
+    }
+
+    /// 取消任务，任务不存在或已结束时返回 false
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.jobs.lock().unwrap().get(job_id) {
+            Some(sender) => sender.send(true).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CancelSignal(watch::Receiver<bool>);
+
+impl CancelSignal {
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    pub async fn cancelled(&mut self) {
+        let _ = self.0.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TailState {
+    Running,
+    Paused,
+    Stopped,
+}
+
+/// 实时消费会话，通过 session id 暂停、恢复和停止
+#[derive(Default)]
+pub struct TailSessions {
+    sessions: Arc<Mutex<HashMap<String, watch::Sender<TailState>>>>,
+}
+
+impl TailSessions {
+    pub fn start(
+        &self,
+        consumer: BaseConsumer,
+        topic: &str,
+        partitions: &[i32],
+        filter: Option<MessageFilter>,
+        codec: Arc<RecordCodec>,
+        channel: Channel<TailEvent>,
+    ) -> EasyKafkaResult<String> {
+        let mut assignment = TopicPartitionList::new();
+        for partition in partitions {
+            assignment.add_partition_offset(topic, *partition, Offset::End)?;
+        }
+        let session_id = Uuid::new_v4().to_string();
+        let (state_sender, state) = watch::channel(TailState::Running);
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id.clone(), state_sender);
+
+        let (record_sender, mut records) = mpsc::channel(TAIL_BUFFER_SIZE);
+        let poll_state = state.clone();
+        let scanned = Arc::new(AtomicU64::new(0));
+        let poll_scanned = scanned.clone();
+        let poll_handle = tauri::async_runtime::spawn_blocking(move || {
+            poll_tail(
+                consumer,
+                assignment,
+                filter,
+                codec,
+                poll_scanned,
+                poll_state,
+                record_sender,
+            )
+        });
+
+        let sessions = self.sessions.clone();
+        let finished_id = session_id.clone();
+        tauri::async_runtime::spawn(async move {
+            forward_tail(&channel, &mut records, &scanned, state).await;
+            // 关闭接收端，让阻塞在缓冲上的消费线程退出
+            drop(records);
+            sessions.lock().unwrap().remove(&finished_id);
+            match poll_handle.await {
+                Ok(Err(e)) => {
+                    let _ = channel.send(TailEvent::Error { msg: e.to_string() });
+                }
+                Err(e) => {
+                    let _ = channel.send(TailEvent::Error { msg: e.to_string() });
+                }
+                Ok(Ok(_)) => {}
+            }
+            let _ = channel.send(TailEvent::Stopped);
+            info!("tail session finished: {}", finished_id);
+        });
+        Ok(session_id)
+    }
+
+    /// 修改会话状态，会话不存在时返回 false
+    pub fn set_state(&self, session_id: &str, state: TailState) -> bool {
+        match self.sessions.lock().unwrap().get(session_id) {
+            Some(sender) => sender.send(state).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// 在阻塞线程中从最新位置开始消费，暂停时暂停分区拉取，只把满足过滤条件的消息放入缓冲
+fn poll_tail(
+    consumer: BaseConsumer,
+    assignment: TopicPartitionList,
+    filter: Option<MessageFilter>,
+    codec: Arc<RecordCodec>,
+    scanned: Arc<AtomicU64>,
+    state: watch::Receiver<TailState>,
+    sender: mpsc::Sender<ConsumerRecord>,
+) -> EasyKafkaResult<()> {
+    consumer.assign(&assignment)?;
+
+    let mut paused = false;
+    loop {
+        let current = *state.borrow();
+        match current {
+            TailState::Stopped => break,
+            TailState::Paused if !paused => {
+                consumer.pause(&assignment)?;
+                paused = true;
+            }
+            TailState::Running if paused => {
+                consumer.resume(&assignment)?;
+                paused = false;
+            }
+            _ => {}
+        }
+        match consumer.poll(Timeout::from(Duration::from_millis(200))) {
+            Some(Ok(message)) => {
+                let record = to_consumer_record(&message, &codec);
+                scanned.fetch_add(1, AtomicOrdering::Relaxed);
+                if !filter.as_ref().is_none_or(|filter| filter.matches(&record)) {
+                    continue;
+                }
+                if sender.blocking_send(record).is_err() {
+                    break;
+                }
+            }
+            Some(Err(e)) => return Err(e.into()),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// 将缓冲中的消息按批推送给前端，会话停止、消费线程退出或前端关闭通道时结束
+async fn forward_tail(
+    channel: &Channel<TailEvent>,
+    records: &mut mpsc::Receiver<ConsumerRecord>,
+    scanned: &AtomicU64,
+    mut state: watch::Receiver<TailState>,
+) {
+    let mut matched = 0;
+    let mut reported_scanned = 0;
+    let mut batch = Vec::with_capacity(TAIL_BATCH_SIZE);
+    let mut ticker = time::interval(Duration::from_millis(100));
+    loop {
+        tokio::select! {
+            record = records.recv() => match record {
+                Some(record) => {
+                    batch.push(record);
+                    if batch.len() < TAIL_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {}
+            changed = state.changed() => {
+                if changed.is_err() || *state.borrow() == TailState::Stopped {
+                    break;
+                }
+                continue;
+            }
+        }
+        // 没有匹配的消息时也定期上报读取数，便于前端展示过滤进度
+        let current_scanned = scanned.load(AtomicOrdering::Relaxed);
+        if batch.is_empty() && current_scanned == reported_scanned {
+            continue;
+        }
+        reported_scanned = current_scanned;
+        let records = std::mem::replace(&mut batch, Vec::with_capacity(TAIL_BATCH_SIZE));
+        matched += records.len() as u64;
+        let event = TailEvent::Records {
+            records,
+            scanned: current_scanned,
+            matched,
+        };
+        if let Err(e) = channel.send(event) {
+            warn!("tail channel closed: {}", e);
+            break;
+        }
+    }
+}
+
+/// 批量发送消息，按固定间隔或目标速率发送，定时通过事件推送进度
+pub async fn run_produce_job(
+    app: AppHandle,
+    job_id: String,
+    producer: FutureProducer,
+    request: BatchProduceRequest,
+    codec: Arc<RecordCodec>,
+    timeout: Duration,
+    mut signal: CancelSignal,
+) {
+    let period = match (request.rate, request.interval) {
+        (Some(rate), _) if rate > 0.0 => Some(Duration::from_secs_f64(1.0 / rate)),
+        (_, Some(interval)) if interval > 0 => Some(Duration::from_millis(interval)),
+        _ => None,
+    };
+    let mut ticker = period.map(|period| {
+        let mut ticker = time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+
+    let mut progress = ProduceJobProgress::new(&job_id, request.count);
+    let mut latencies = LatencyHistogram::new();
+    let mut pending = JoinSet::new();
+    let mut last_emit = Instant::now();
+
+    while progress.sent < request.count {
+        if let Some(ticker) = ticker.as_mut() {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = signal.cancelled() => break,
+            }
+        } else if signal.is_cancelled() {
+            break;
+        }
+
+        if pending.len() >= PRODUCE_JOB_IN_FLIGHT {
+            if let Some(joined) = pending.join_next().await {
+                progress.record(joined, &mut latencies);
+            }
+        }
+        let producer = producer.clone();
+        let message = request.message.clone();
+        let codec = codec.clone();
+        pending.spawn(async move {
+            let start = Instant::now();
+            let result = send_message(&producer, &message, &codec, timeout).await;
+            (result, start.elapsed())
+        });
+        progress.sent += 1;
+
+        // 收集已经完成的投递结果
+        while let Some(joined) = pending.try_join_next() {
+            progress.record(joined, &mut latencies);
+        }
+        if last_emit.elapsed() >= Duration::from_millis(500) {
+            progress.update_latency(&latencies);
+            emit_progress(&app, &progress);
+            last_emit = Instant::now();
+        }
+    }
+
+    while let Some(joined) = pending.join_next().await {
+        progress.record(joined, &mut latencies);
+    }
+    progress.cancelled = signal.is_cancelled();
+    progress.finished = true;
+    progress.update_latency(&latencies);
+    emit_progress(&app, &progress);
+    info!("produce job finished: {:?}", progress);
+}
+
+fn emit_progress(app: &AppHandle, progress: &ProduceJobProgress) {
+    if let Err(e) = app.emit(PRODUCE_JOB_PROGRESS_EVENT, progress.clone()) {
+        warn!("emit produce job progress failed: {}", e);
+    }
+}
+
+impl ProduceJobProgress {
+    /// 记录一条消息的投递结果，发送任务异常退出时按失败计数
+    fn record(
+        &mut self,
+        joined: Result<(DeliveryResult, Duration), JoinError>,
+        latencies: &mut LatencyHistogram,
+    ) {
+        match joined {
+            Ok((result, elapsed)) => {
+                match result {
+                    DeliveryResult::Delivered { .. } => self.success += 1,
+                    DeliveryResult::Failed { .. } => self.failed += 1,
+                }
+                latencies.record(elapsed.as_millis() as u64);
+            }
+            Err(e) => {
+                warn!("produce job send task failed: {}", e);
+                self.failed += 1;
+            }
+        }
+    }
+
+    fn update_latency(&mut self, latencies: &LatencyHistogram) {
+        self.p50 = latencies.percentile(50.0);
+        self.p95 = latencies.percentile(95.0);
+        self.p99 = latencies.percentile(99.0);
+    }
+}
+
+/// 投递延迟直方图，占用固定内存，小于 LATENCY_EXACT_MILLIS 的延迟精确记录
+struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: vec![0; latency_bucket(u64::MAX) + 1],
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, millis: u64) {
+        self.buckets[latency_bucket(millis)] += 1;
+        self.count += 1;
+    }
+
+    /// 计算百分位数（最近秩法），返回所在桶的下界
+    fn percentile(&self, percent: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((percent / 100.0 * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return latency_bucket_floor(index);
+            }
+        }
+        0
+    }
+}
+
+fn latency_bucket(millis: u64) -> usize {
+    if millis < LATENCY_EXACT_MILLIS {
+        return millis as usize;
+    }
+    let exponent = (u64::BITS - 1 - millis.leading_zeros()) as u64;
+    let shift = exponent - LATENCY_SUB_BUCKETS.trailing_zeros() as u64;
+    let segment = exponent - LATENCY_EXACT_MILLIS.trailing_zeros() as u64;
+    (LATENCY_EXACT_MILLIS + segment * LATENCY_SUB_BUCKETS + (millis >> shift) - LATENCY_SUB_BUCKETS)
+        as usize
+}
+
+fn latency_bucket_floor(index: usize) -> u64 {
+    let index = index as u64;
+    if index < LATENCY_EXACT_MILLIS {
+        return index;
+    }
+    let segment = (index - LATENCY_EXACT_MILLIS) / LATENCY_SUB_BUCKETS;
+    let offset = (index - LATENCY_EXACT_MILLIS) % LATENCY_SUB_BUCKETS;
+    let shift = segment + LATENCY_EXACT_MILLIS.trailing_zeros() as u64
+        - LATENCY_SUB_BUCKETS.trailing_zeros() as u64;
+    (LATENCY_SUB_BUCKETS + offset) << shift
+}
+
+enum SearchMessage {
+    Matched(ConsumerRecord),
+    Failed(i32, String),
+}
+
+/// 搜索线程之间共享的状态
+struct SearchContext {
+    connect: cluster::Model,
+    topic: String,
+    filter: MessageFilter,
+    codec: Arc<RecordCodec>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    timeout: Duration,
+    stop: AtomicBool,
+    scanned: AtomicU64,
+    progress: Mutex<HashMap<i32, PartitionSearchProgress>>,
+}
+
+/// 并行搜索 topic 的各个分区，匹配的消息和分区进度通过 channel 推送
+pub async fn run_search_job(
+    channel: Channel<SearchEvent>,
+    connect: cluster::Model,
+    request: SearchTopicRequest,
+    partitions: Vec<i32>,
+    filter: MessageFilter,
+    codec: Arc<RecordCodec>,
+    mut signal: CancelSignal,
+) {
+    let timeout = Duration::from_millis(connect.timeout as u64);
+    let context = Arc::new(SearchContext {
+        connect,
+        topic: request.topic.clone(),
+        filter,
+        codec,
+        start_timestamp: request.start_timestamp,
+        end_timestamp: request.end_timestamp,
+        timeout,
+        stop: AtomicBool::new(false),
+        scanned: AtomicU64::new(0),
+        progress: Mutex::new(HashMap::new()),
+    });
+    let (sender, mut receiver) = mpsc::channel(TAIL_BUFFER_SIZE);
+    let semaphore = Arc::new(Semaphore::new(SEARCH_PARALLELISM));
+    for partition in partitions {
+        let context = context.clone();
+        let sender = sender.clone();
+        let semaphore = semaphore.clone();
+        tauri::async_runtime::spawn(async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return;
+            };
+            if context.stop.load(AtomicOrdering::Relaxed) {
+                return;
+            }
+            let worker_sender = sender.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                search_partition(&context, partition, &worker_sender)
+            })
+            .await;
+            let error = match result {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(msg) = error {
+                let _ = sender.send(SearchMessage::Failed(partition, msg)).await;
+            }
+        });
+    }
+    drop(sender);
+
+    let max_results = request.max_results.unwrap_or(u64::MAX);
+    let mut matched = 0;
+    let mut cancelled = false;
+    let mut ticker = time::interval(Duration::from_millis(500));
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(SearchMessage::Matched(record)) => {
+                    if matched >= max_results {
+                        continue;
+                    }
+                    matched += 1;
+                    if matched >= max_results {
+                        context.stop.store(true, AtomicOrdering::Relaxed);
+                    }
+                    if let Err(e) = channel.send(SearchEvent::Matched { record }) {
+                        warn!("search channel closed: {}", e);
+                        context.stop.store(true, AtomicOrdering::Relaxed);
+                        break;
+                    }
+                }
+                Some(SearchMessage::Failed(partition, msg)) => {
+                    let _ = channel.send(SearchEvent::Error { partition, msg });
+                }
+                None => break,
+            },
+            _ = ticker.tick() => send_search_progress(&channel, &context, matched),
+            _ = signal.cancelled(), if !cancelled => {
+                cancelled = true;
+                context.stop.store(true, AtomicOrdering::Relaxed);
+            }
+        }
+    }
+
+    send_search_progress(&channel, &context, matched);
+    let _ = channel.send(SearchEvent::Finished {
+        scanned: context.scanned.load(AtomicOrdering::Relaxed),
+        matched,
+        cancelled,
+    });
+    info!(
+        "search job finished, topic: {}, matched: {}, cancelled: {}",
+        request.topic, matched, cancelled
+    );
+}
+
+fn send_search_progress(channel: &Channel<SearchEvent>, context: &SearchContext, matched: u64) {
+    let mut partitions = context
+        .progress
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    partitions.sort_by_key(|item| item.partition);
+    let _ = channel.send(SearchEvent::Progress {
+        partitions,
+        scanned: context.scanned.load(AtomicOrdering::Relaxed),
+        matched,
+    });
+}
+
+/// 在阻塞线程中搜索单个分区，从时间窗口起点读到终点
+fn search_partition(
+    context: &SearchContext,
+    partition: i32,
+    sender: &mpsc::Sender<SearchMessage>,
+) -> EasyKafkaResult<()> {
+    let consumer = create_consumer(&context.connect)?;
+    let topic = context.topic.as_str();
+    let (low, high) =
+        consumer.fetch_watermarks(topic, partition, Timeout::from(context.timeout))?;
+    let offset_at = |timestamp: i64| -> EasyKafkaResult<i64> {
+        let offsets = fetch_offsets_for_timestamp(
+            &consumer,
+            topic,
+            &[partition],
+            timestamp,
+            context.timeout,
+        )?;
+        Ok(match offsets.get(&partition) {
+            Some(Offset::Offset(offset)) => *offset,
+            _ => high,
+        })
+    };
+    let start = match context.start_timestamp {
+        Some(timestamp) => offset_at(timestamp)?,
+        None => low,
+    };
+    let end = match context.end_timestamp {
+        Some(timestamp) => offset_at(timestamp)?,
+        None => high,
+    };
+    update_search_progress(context, partition, start, end, start);
+    if start >= end {
+        return Ok(());
+    }
+
+    let mut assignment = TopicPartitionList::new();
+    assignment.add_partition_offset(topic, partition, Offset::Offset(start))?;
+    consumer.assign(&assignment)?;
+
+    let mut current = start;
+    let mut count = 0;
+    while current < end && !context.stop.load(AtomicOrdering::Relaxed) {
+        match consumer.poll(Timeout::from(Duration::from_millis(200))) {
+            Some(Ok(message)) => {
+                if message.offset() >= end {
+                    current = end;
+                    break;
+                }
+                current = message.offset() + 1;
+                let record = to_consumer_record(&message, &context.codec);
+                context.scanned.fetch_add(1, AtomicOrdering::Relaxed);
+                if context.filter.matches(&record)
+                    && sender
+                        .blocking_send(SearchMessage::Matched(record))
+                        .is_err()
+                {
+                    break;
+                }
+                count += 1;
+                if count % SEARCH_PROGRESS_STEP == 0 {
+                    update_search_progress(context, partition, start, end, current);
+                }
+            }
+            Some(Err(e)) => return Err(e.into()),
+            None => {
+                // 事务标记和压缩会让水位差大于实际消息数，拉取位置到达终点即结束
+                let position = consumer.position()?;
+                if let Some(Offset::Offset(offset)) = position
+                    .find_partition(topic, partition)
+                    .map(|item| item.offset())
+                {
+                    if offset >= end {
+                        current = end;
+                    }
+                }
+            }
+        }
+    }
+    update_search_progress(context, partition, start, end, current);
+    Ok(())
+}
+
+fn update_search_progress(
+    context: &SearchContext,
+    partition: i32,
+    start: i64,
+    end: i64,
+    current: i64,
+) {
+    let percent = if end <= start {
+        100.0
+    } else {
+        (current - start) as f64 / (end - start) as f64 * 100.0
+    };
+    context.progress.lock().unwrap().insert(
+        partition,
+        PartitionSearchProgress {
+            partition,
+            start_offset: start,
+            end_offset: end,
+            current_offset: current,
+            percent,
+        },
+    );
+}
+
+#[cfg(test)]
+mod executor_test {
+    use crate::infra::executor::LatencyHistogram;
+
+    #[test]
+    fn latency_histogram_test() {
+        let mut latencies = LatencyHistogram::new();
+        assert_eq!(latencies.percentile(50.0), 0);
+        (1..=100).for_each(|item| latencies.record(item));
+        assert_eq!(latencies.percentile(50.0), 50);
+        assert_eq!(latencies.percentile(95.0), 95);
+        assert_eq!(latencies.percentile(99.0), 99);
+
+        let mut latencies = LatencyHistogram::new();
+        latencies.record(7);
+        assert_eq!(latencies.percentile(99.0), 7);
+
+        // 超过精确范围的延迟按桶的下界返回，误差不超过 1/64
+        let mut latencies = LatencyHistogram::new();
+        latencies.record(1000);
+        latencies.record(1001);
+        latencies.record(u64::MAX);
+        assert_eq!(latencies.percentile(50.0), 1000);
+        assert!(latencies.percentile(100.0) >= u64::MAX / 64 * 63);
+    }
+}
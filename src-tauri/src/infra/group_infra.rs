@@ -0,0 +1,600 @@
+use crate::entity::db_entity::cluster;
+use crate::entity::request::consumer_group::{GroupSelection, ResetOffsetsRequest, ResetPosition};
+use crate::entity::response::consumer_group::{
+    BrokerNode, ConsumerGroupDetail, ConsumerGroupResponse, GroupMember, GroupOperationResult,
+    GroupPartitionLag, MemberAssignment, OffsetResetItem, PartitionOffset,
+};
+use crate::infra::kafka_infra::{
+    create_client_config, fetch_offsets_for_timestamp, is_internal_topic,
+};
+use crate::utils::group_protocol::{coordinator_partition, decode_assignment};
+use crate::{EasyKafkaError, EasyKafkaResult};
+use rdkafka::consumer::{BaseConsumer, CommitMode, Consumer};
+use rdkafka::groups::{GroupInfo, GroupList};
+use rdkafka::metadata::Metadata;
+use rdkafka::util::Timeout;
+use rdkafka::{Offset, TopicPartitionList};
+use rdkafka_sys as rdsys;
+use rdkafka_sys::types::{RDKafkaErrorCode, RDKafkaRespErr};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{c_int, CStr, CString};
+use std::ptr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 消费者组位移所在的内部 topic
+const CONSUMER_OFFSETS_TOPIC: &str = "__consumer_offsets";
+/// 不存在的消费者组在组列表中的状态
+const DEAD_STATE: &str = "Dead";
+
+/// 以指定消费者组的身份创建消费者，只用于提交位移，不订阅 topic，不会加入消费者组
+pub fn create_group_consumer(
+    connect: &cluster::Model,
+    group_id: &str,
+) -> EasyKafkaResult<BaseConsumer> {
+    let mut client_config = create_client_config(connect)?;
+    client_config
+        .set("group.id", group_id)
+        .set("enable.auto.commit", "false")
+        .set("enable.auto.offset.store", "false");
+    Ok(client_config.create()?)
+}
+
+/// 查询消费者组列表，group_id 为空时查询所有消费者组
+pub fn fetch_group_list(
+    consumer: &BaseConsumer,
+    group_id: Option<&str>,
+    timeout: Duration,
+) -> EasyKafkaResult<GroupList> {
+    Ok(consumer.fetch_group_list(group_id, Timeout::from(timeout))?)
+}
+
+/// 查询不存在的消费者组时 broker 返回 Dead 状态
+pub fn is_live_group(group: &GroupInfo) -> bool {
+    group.state() != DEAD_STATE
+}
+
+/// 查询消费者组在所有非内部 topic 上已提交的位移，返回 (topic, partition) -> offset
+pub fn fetch_committed_offsets(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    metadata: &Metadata,
+    timeout: Duration,
+) -> EasyKafkaResult<BTreeMap<(String, i32), i64>> {
+    committed_offsets(consumer, group_id, offsets_assignment(metadata), timeout)
+}
+
+/// 所有非内部 topic 的分区，用于查询消费者组已提交的位移
+pub fn offsets_assignment(metadata: &Metadata) -> TopicPartitionList {
+    let mut assignment = TopicPartitionList::new();
+    for topic in metadata
+        .topics()
+        .iter()
+        .filter(|topic| !is_internal_topic(topic.name()) && topic.error().is_none())
+    {
+        for partition in topic.partitions() {
+            assignment.add_partition(topic.name(), partition.id());
+        }
+    }
+    assignment
+}
+
+/// 查询消费者组在 topic 指定分区上已提交的位移，没有提交过的分区不在结果中
+pub fn fetch_partition_committed(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    topic: &str,
+    partitions: &[i32],
+    timeout: Duration,
+) -> EasyKafkaResult<HashMap<i32, i64>> {
+    let mut assignment = TopicPartitionList::new();
+    for partition in partitions {
+        assignment.add_partition(topic, *partition);
+    }
+    Ok(committed_offsets(consumer, group_id, assignment, timeout)?
+        .into_iter()
+        .map(|((_, partition), offset)| (partition, offset))
+        .collect())
+}
+
+/// 查询消费者组在指定分区上已提交的位移，没有提交过的分区不在结果中。
+/// 通过共享的客户端发送 OffsetFetch 请求，不需要以消费者组的身份创建客户端，
+/// rdkafka 没有封装 ListConsumerGroupOffsets，这里直接调用 librdkafka
+pub fn committed_offsets(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    assignment: TopicPartitionList,
+    timeout: Duration,
+) -> EasyKafkaResult<BTreeMap<(String, i32), i64>> {
+    if assignment.count() == 0 {
+        return Ok(BTreeMap::new());
+    }
+    let group = CString::new(group_id).map_err(|e| EasyKafkaError::StdError(Box::new(e)))?;
+    let client = consumer.client().native_ptr();
+    // SAFETY: 请求会复制 group 和分区列表，event、请求和队列都在本函数内销毁
+    let result = unsafe {
+        let request =
+            rdsys::rd_kafka_ListConsumerGroupOffsets_new(group.as_ptr(), assignment.ptr());
+        let queue = rdsys::rd_kafka_queue_new(client);
+        let mut requests = [request];
+        rdsys::rd_kafka_ListConsumerGroupOffsets(
+            client,
+            requests.as_mut_ptr(),
+            requests.len(),
+            ptr::null(),
+            queue,
+        );
+        let event = rdsys::rd_kafka_queue_poll(queue, timeout.as_millis() as c_int);
+        let result = read_list_offsets_event(event);
+        if !event.is_null() {
+            rdsys::rd_kafka_event_destroy(event);
+        }
+        rdsys::rd_kafka_ListConsumerGroupOffsets_destroy(request);
+        rdsys::rd_kafka_queue_destroy(queue);
+        result
+    };
+    result.map_err(|msg| EasyKafkaError::StdError(msg.into()))
+}
+
+/// 读取 ListConsumerGroupOffsets 的结果，只保留已提交过位移的分区
+unsafe fn read_list_offsets_event(
+    event: *mut rdsys::rd_kafka_event_t,
+) -> Result<BTreeMap<(String, i32), i64>, String> {
+    if event.is_null() {
+        return Err("查询位移超时".to_string());
+    }
+    if rdsys::rd_kafka_event_error(event) != RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+        return Err(CStr::from_ptr(rdsys::rd_kafka_event_error_string(event))
+            .to_string_lossy()
+            .to_string());
+    }
+    let result = rdsys::rd_kafka_event_ListConsumerGroupOffsets_result(event);
+    let mut count = 0;
+    let groups = rdsys::rd_kafka_ListConsumerGroupOffsets_result_groups(result, &mut count);
+    let mut offsets = BTreeMap::new();
+    for index in 0..count {
+        let group = *groups.add(index);
+        let error = rdsys::rd_kafka_group_result_error(group);
+        if !error.is_null() {
+            return Err(CStr::from_ptr(rdsys::rd_kafka_error_string(error))
+                .to_string_lossy()
+                .to_string());
+        }
+        let partitions = rdsys::rd_kafka_group_result_partitions(group);
+        if partitions.is_null() {
+            continue;
+        }
+        for index in 0..(*partitions).cnt as usize {
+            let partition = &*(*partitions).elems.add(index);
+            if partition.err == RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR && partition.offset >= 0
+            {
+                let topic = CStr::from_ptr(partition.topic)
+                    .to_string_lossy()
+                    .to_string();
+                offsets.insert((topic, partition.partition), partition.offset);
+            }
+        }
+    }
+    Ok(offsets)
+}
+
+/// 查询消费者组的活跃成员数，有活跃成员时不能修改位移
+pub fn count_active_members(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    timeout: Duration,
+) -> EasyKafkaResult<usize> {
+    let group_list = fetch_group_list(consumer, Some(group_id), timeout)?;
+    Ok(group_list
+        .groups()
+        .iter()
+        .filter(|group| group.name() == group_id && is_live_group(group))
+        .map(|group| group.members().len())
+        .sum())
+}
+
+/// 计算消费者组在各分区重置后的位移，结果限制在分区水位范围内
+pub fn plan_offset_reset(
+    consumer: &BaseConsumer,
+    request: &ResetOffsetsRequest,
+    partitions: &[i32],
+    timeout: Duration,
+) -> EasyKafkaResult<Vec<OffsetResetItem>> {
+    let topic = request.topic.as_str();
+    let committed =
+        fetch_partition_committed(consumer, &request.group_id, topic, partitions, timeout)?;
+    let timestamp = match request.position {
+        ResetPosition::Timestamp { timestamp } => Some(timestamp),
+        ResetPosition::Duration { millis } => Some(current_millis() - millis),
+        _ => None,
+    };
+    let timestamp_offsets = match timestamp {
+        Some(timestamp) => {
+            fetch_offsets_for_timestamp(consumer, topic, partitions, timestamp, timeout)?
+        }
+        None => HashMap::new(),
+    };
+
+    let mut items = Vec::new();
+    for partition in partitions {
+        let (low, high) = consumer.fetch_watermarks(topic, *partition, Timeout::from(timeout))?;
+        let current_offset = committed.get(partition).copied();
+        let proposed_offset = match request.position {
+            ResetPosition::Earliest => low,
+            ResetPosition::Latest => high,
+            ResetPosition::Offset { offset } => offset,
+            ResetPosition::Shift { by } => current_offset.unwrap_or(low) + by,
+            ResetPosition::Timestamp { .. } | ResetPosition::Duration { .. } => {
+                match timestamp_offsets.get(partition) {
+                    Some(Offset::Offset(offset)) => *offset,
+                    _ => high,
+                }
+            }
+        }
+        .clamp(low, high);
+        items.push(OffsetResetItem {
+            partition: *partition,
+            current_offset,
+            proposed_offset,
+            low_watermark: low,
+            high_watermark: high,
+        });
+    }
+    Ok(items)
+}
+
+/// 查询分区的水位，和提交前后的位移一起返回给前端
+pub fn describe_offsets(
+    consumer: &BaseConsumer,
+    topic: &str,
+    current: &HashMap<i32, i64>,
+    proposed: &[PartitionOffset],
+    timeout: Duration,
+) -> EasyKafkaResult<Vec<OffsetResetItem>> {
+    let mut items = Vec::new();
+    for item in proposed {
+        let (low, high) =
+            consumer.fetch_watermarks(topic, item.partition, Timeout::from(timeout))?;
+        items.push(OffsetResetItem {
+            partition: item.partition,
+            current_offset: current.get(&item.partition).copied(),
+            proposed_offset: item.offset,
+            low_watermark: low,
+            high_watermark: high,
+        });
+    }
+    Ok(items)
+}
+
+/// 以消费者组的身份同步提交位移
+pub fn commit_group_offsets(
+    connect: &cluster::Model,
+    group_id: &str,
+    topic: &str,
+    offsets: &[PartitionOffset],
+) -> EasyKafkaResult<()> {
+    let mut assignment = TopicPartitionList::new();
+    for item in offsets {
+        assignment.add_partition_offset(topic, item.partition, Offset::Offset(item.offset))?;
+    }
+    if assignment.count() == 0 {
+        return Ok(());
+    }
+    let consumer = create_group_consumer(connect, group_id)?;
+    consumer.commit(&assignment, CommitMode::Sync)?;
+    Ok(())
+}
+
+/// 根据指定的组id和正则选择消费者组，指定的组不存在或不满足条件时放在错误结果中
+pub fn select_groups(
+    group_list: &GroupList,
+    selection: &GroupSelection,
+    regex: Option<&Regex>,
+) -> (Vec<String>, Vec<GroupOperationResult>) {
+    let groups = group_list
+        .groups()
+        .iter()
+        .filter(|group| is_live_group(group))
+        .map(|group| (group.name(), group))
+        .collect::<HashMap<_, _>>();
+    let mut selected: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    for group_id in selection.group_ids.iter() {
+        match groups.get(group_id.as_str()) {
+            None => errors.push(GroupOperationResult::error(
+                group_id,
+                "消费者组不存在".to_string(),
+            )),
+            Some(group) if selection.only_empty && !group.members().is_empty() => {
+                errors.push(GroupOperationResult::error(
+                    group_id,
+                    format!(
+                        "消费者组状态为{}，有{}个成员",
+                        group.state(),
+                        group.members().len()
+                    ),
+                ))
+            }
+            Some(_) if !selected.contains(group_id) => selected.push(group_id.clone()),
+            Some(_) => {}
+        }
+    }
+    if let Some(regex) = regex {
+        let mut matched = groups
+            .values()
+            .filter(|group| regex.is_match(group.name()))
+            .filter(|group| !selection.only_empty || group.members().is_empty())
+            .filter(|group| !selected.iter().any(|item| item == group.name()))
+            .map(|group| group.name().to_string())
+            .collect::<Vec<_>>();
+        matched.sort();
+        selected.extend(matched);
+    }
+    (selected, errors)
+}
+
+/// 删除消费者组在 topic 指定分区上已提交的位移，消费者组不能正在订阅该 topic。
+/// rdkafka 没有封装 DeleteConsumerGroupOffsets，这里直接调用 librdkafka
+pub fn delete_committed_offsets(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    topic: &str,
+    partitions: &[i32],
+    timeout: Duration,
+) -> Result<(), String> {
+    let group = CString::new(group_id).map_err(|e| e.to_string())?;
+    let mut assignment = TopicPartitionList::new();
+    for partition in partitions {
+        assignment.add_partition(topic, *partition);
+    }
+    let client = consumer.client().native_ptr();
+    // SAFETY: 请求会复制 group 和分区列表，event、请求和队列都在本函数内销毁
+    unsafe {
+        let request =
+            rdsys::rd_kafka_DeleteConsumerGroupOffsets_new(group.as_ptr(), assignment.ptr());
+        let queue = rdsys::rd_kafka_queue_new(client);
+        let mut requests = [request];
+        rdsys::rd_kafka_DeleteConsumerGroupOffsets(
+            client,
+            requests.as_mut_ptr(),
+            requests.len(),
+            ptr::null(),
+            queue,
+        );
+        let event = rdsys::rd_kafka_queue_poll(queue, timeout.as_millis() as c_int);
+        let result = read_delete_offsets_event(event);
+        if !event.is_null() {
+            rdsys::rd_kafka_event_destroy(event);
+        }
+        rdsys::rd_kafka_DeleteConsumerGroupOffsets_destroy(request);
+        rdsys::rd_kafka_queue_destroy(queue);
+        result
+    }
+}
+
+/// 读取 DeleteConsumerGroupOffsets 的结果，汇总请求、消费者组和分区三个层级的错误
+unsafe fn read_delete_offsets_event(event: *mut rdsys::rd_kafka_event_t) -> Result<(), String> {
+    if event.is_null() {
+        return Err("删除位移超时".to_string());
+    }
+    if rdsys::rd_kafka_event_error(event) != RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+        return Err(CStr::from_ptr(rdsys::rd_kafka_event_error_string(event))
+            .to_string_lossy()
+            .to_string());
+    }
+    let result = rdsys::rd_kafka_event_DeleteConsumerGroupOffsets_result(event);
+    let mut count = 0;
+    let groups = rdsys::rd_kafka_DeleteConsumerGroupOffsets_result_groups(result, &mut count);
+    let mut errors = Vec::new();
+    for index in 0..count {
+        let group = *groups.add(index);
+        let error = rdsys::rd_kafka_group_result_error(group);
+        if !error.is_null() {
+            errors.push(
+                CStr::from_ptr(rdsys::rd_kafka_error_string(error))
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+        let partitions = rdsys::rd_kafka_group_result_partitions(group);
+        if partitions.is_null() {
+            continue;
+        }
+        for index in 0..(*partitions).cnt as usize {
+            let partition = &*(*partitions).elems.add(index);
+            if partition.err != RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+                errors.push(format!(
+                    "分区{}: {}",
+                    partition.partition,
+                    RDKafkaErrorCode::from(partition.err)
+                ));
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join(", "))
+    }
+}
+
+/// 分区高水位缓存，多个消费者组消费同一分区时只查询一次
+pub struct HighWatermarks<'a> {
+    consumer: &'a BaseConsumer,
+    timeout: Duration,
+    cache: HashMap<(String, i32), i64>,
+}
+
+impl<'a> HighWatermarks<'a> {
+    pub fn new(consumer: &'a BaseConsumer, timeout: Duration) -> Self {
+        Self {
+            consumer,
+            timeout,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, topic: &str, partition: i32) -> EasyKafkaResult<i64> {
+        let key = (topic.to_string(), partition);
+        if let Some(high) = self.cache.get(&key) {
+            return Ok(*high);
+        }
+        let (_, high) =
+            self.consumer
+                .fetch_watermarks(topic, partition, Timeout::from(self.timeout))?;
+        self.cache.insert(key, high);
+        Ok(high)
+    }
+}
+
+/// 汇总消费者组的成员数、已提交位移的分区数和总 lag
+pub fn summarize_group(
+    consumer: &BaseConsumer,
+    group: &GroupInfo,
+    metadata: &Metadata,
+    watermarks: &mut HighWatermarks,
+    timeout: Duration,
+) -> EasyKafkaResult<ConsumerGroupResponse> {
+    let committed = fetch_committed_offsets(consumer, group.name(), metadata, timeout)?;
+    let mut total_lag = 0;
+    for ((topic, partition), offset) in committed.iter() {
+        total_lag += (watermarks.get(topic, *partition)? - offset).max(0);
+    }
+    Ok(ConsumerGroupResponse {
+        group_id: group.name().to_string(),
+        state: group.state().to_string(),
+        protocol_type: group.protocol_type().to_string(),
+        members: group.members().len(),
+        partitions: committed.len(),
+        total_lag,
+    })
+}
+
+/// 查询消费者组的成员、coordinator 和每个分区的 lag，消费者组不存在时返回 None
+pub fn describe_group(
+    consumer: &BaseConsumer,
+    group_id: &str,
+    timeout: Duration,
+) -> EasyKafkaResult<Option<ConsumerGroupDetail>> {
+    let group_list = fetch_group_list(consumer, Some(group_id), timeout)?;
+    let group = match group_list
+        .groups()
+        .iter()
+        .find(|group| group.name() == group_id && is_live_group(group))
+    {
+        Some(group) => group,
+        None => return Ok(None),
+    };
+    let metadata = consumer.fetch_metadata(None, Timeout::from(timeout))?;
+    let leaders = metadata
+        .topics()
+        .iter()
+        .flat_map(|topic| {
+            topic.partitions().iter().map(move |partition| {
+                (
+                    (topic.name().to_string(), partition.id()),
+                    partition.leader(),
+                )
+            })
+        })
+        .collect::<HashMap<_, _>>();
+
+    // 分区到成员的映射，用于展示每个分区由哪个成员消费
+    let mut members = Vec::new();
+    let mut owners = HashMap::new();
+    for member in group.members() {
+        let assignments = member
+            .assignment()
+            .and_then(|bytes| decode_assignment(bytes).ok())
+            .unwrap_or_default();
+        for (topic, partitions) in assignments.iter() {
+            for partition in partitions {
+                owners.insert((topic.clone(), *partition), members.len());
+            }
+        }
+        members.push(GroupMember {
+            member_id: member.id().to_string(),
+            client_id: member.client_id().to_string(),
+            host: member.client_host().trim_start_matches('/').to_string(),
+            assignments: assignments
+                .into_iter()
+                .map(|(topic, partitions)| MemberAssignment { topic, partitions })
+                .collect(),
+        });
+    }
+
+    let committed = fetch_committed_offsets(consumer, group_id, &metadata, timeout)?;
+    let mut keys = committed.keys().cloned().collect::<Vec<_>>();
+    keys.extend(
+        owners
+            .keys()
+            .filter(|key| !committed.contains_key(key))
+            .cloned(),
+    );
+    keys.sort();
+
+    let mut watermarks = HighWatermarks::new(consumer, timeout);
+    let mut partitions = Vec::new();
+    let mut total_lag = 0;
+    for key in keys {
+        let (topic, partition) = &key;
+        let end_offset = watermarks.get(topic, *partition)?;
+        let current_offset = committed.get(&key).copied();
+        let lag = current_offset.map(|offset| (end_offset - offset).max(0));
+        total_lag += lag.unwrap_or(0);
+        let owner = owners.get(&key).map(|index| &members[*index]);
+        partitions.push(GroupPartitionLag {
+            topic: topic.clone(),
+            partition: *partition,
+            current_offset,
+            end_offset,
+            lag,
+            leader: leaders.get(&key).copied().unwrap_or(-1),
+            consumer_id: owner.map(|member| member.member_id.clone()),
+            client_id: owner.map(|member| member.client_id.clone()),
+            host: owner.map(|member| member.host.clone()),
+        });
+    }
+
+    Ok(Some(ConsumerGroupDetail {
+        group_id: group.name().to_string(),
+        state: group.state().to_string(),
+        protocol_type: group.protocol_type().to_string(),
+        protocol: group.protocol().to_string(),
+        coordinator: find_coordinator(&metadata, group_id),
+        members,
+        partitions,
+        total_lag,
+    }))
+}
+
+/// 根据 __consumer_offsets 分区的 leader 推算消费者组的 coordinator，
+/// 该 topic 的元数据有错误或还没有分区时返回 None
+fn find_coordinator(metadata: &Metadata, group_id: &str) -> Option<BrokerNode> {
+    let topic = metadata
+        .topics()
+        .iter()
+        .find(|topic| topic.name() == CONSUMER_OFFSETS_TOPIC && topic.error().is_none())?;
+    let partition = coordinator_partition(group_id, topic.partitions().len() as i32)?;
+    let leader = topic
+        .partitions()
+        .iter()
+        .find(|item| item.id() == partition)?
+        .leader();
+    metadata
+        .brokers()
+        .iter()
+        .find(|broker| broker.id() == leader)
+        .map(|broker| BrokerNode {
+            id: broker.id(),
+            host: broker.host().to_string(),
+            port: broker.port(),
+        })
+}
+
+pub fn current_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
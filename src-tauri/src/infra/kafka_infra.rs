@@ -1,38 +1,522 @@
-use crate::config::EasyKafkaConfig;
-use crate::entity::db_entity::cluster;
-use crate::infra::sql_infra::get_connect;
+use crate::entity::db_entity::{cluster, topics};
+use crate::entity::request::consumer::StartPosition;
+use crate::entity::request::producer::ProduceMessageRequest;
+use crate::entity::request::topic::{FormatConfig, MessageFormat, TopicFormat};
+use crate::entity::response::consumer::{ConsumeMessagesResponse, ConsumerRecord, RecordHeader};
+use crate::entity::response::producer::DeliveryResult;
+use crate::entity::response::topic::{PartitionResponse, TopicResponse, TopicResponseBuilder};
+use crate::infra::schema_registry::{RegistryCodec, SchemaRegistryClient};
+use crate::utils::codec::{FieldCodec, RecordCodec};
+use crate::utils::message_filter::MessageFilter;
+use crate::utils::security::{describe_auth_failure, security_properties};
 use crate::{EasyKafkaError, EasyKafkaResult};
-use log::info;
+use log::{error, info};
+use rdkafka::client::ClientContext;
+use rdkafka::consumer::{BaseConsumer, Consumer, ConsumerContext};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders, Timestamp};
+use rdkafka::metadata::{Metadata, MetadataTopic};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
 use rdkafka::{admin::AdminClient, client::DefaultClientContext, ClientConfig};
-use sea_orm::EntityTrait;
+use rdkafka::{Offset, TopicPartitionList};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
-pub async fn create_kafka_admin_client(
+/// 创建手动分配分区的消费者，不加入消费者组，也不提交位移
+pub async fn create_kafka_consumer(
     token: &str,
-    config: &EasyKafkaConfig,
-) -> EasyKafkaResult<(AdminClient<DefaultClientContext>, cluster::Model)> {
-    let db_connect = get_connect(&config.database).await?;
-    let find_result = cluster::Entity::find_by_id(token).one(&db_connect).await?;
-
-    match find_result {
-        Some(connect) => {
-            info!("create_kafka_admin_client connect: {:?}", connect);
-            let mut client_config = ClientConfig::new();
-            client_config.set("bootstrap.servers", connect.servers.as_str());
-            if let Some(username) = connect.username.as_deref() {
-                client_config.set("sasl.username", username);
-            }
-            if let Some(password) = connect.password.as_deref() {
-                client_config.set("sasl.password", password);
-            }
-            if connect.ssl {
-                client_config.set(
-                    "security.protocol",
-                    connect.sasl.as_deref().unwrap_or("PLAINT"),
-                );
+    db: &DatabaseConnection,
+) -> EasyKafkaResult<(BaseConsumer, cluster::Model)> {
+    let connect = find_cluster(token, db).await?;
+    info!(
+        "create_kafka_consumer cluster: {}, servers: {}",
+        connect.id, connect.servers
+    );
+    Ok((create_consumer(&connect)?, connect))
+}
+
+/// 根据已查询到的集群配置创建消费者，用于需要多个消费者并行读取的场景
+pub fn create_consumer(connect: &cluster::Model) -> EasyKafkaResult<BaseConsumer> {
+    let mut client_config = create_client_config(connect)?;
+    client_config
+        .set("group.id", format!("easy-kafka-{}", Uuid::new_v4()))
+        .set("enable.auto.commit", "false")
+        .set("enable.auto.offset.store", "false")
+        .set("enable.partition.eof", "false");
+    Ok(client_config.create()?)
+}
+
+pub fn create_admin_client(
+    connect: &cluster::Model,
+) -> EasyKafkaResult<AdminClient<DefaultClientContext>> {
+    info!(
+        "create_admin_client cluster: {}, servers: {}",
+        connect.id, connect.servers
+    );
+    Ok(create_client_config(connect)?.create()?)
+}
+
+pub fn create_producer(connect: &cluster::Model) -> EasyKafkaResult<FutureProducer> {
+    info!(
+        "create_producer cluster: {}, servers: {}",
+        connect.id, connect.servers
+    );
+    let mut client_config = create_client_config(connect)?;
+    client_config.set("message.timeout.ms", connect.timeout.to_string());
+    Ok(client_config.create()?)
+}
+
+pub async fn find_cluster(token: &str, db: &DatabaseConnection) -> EasyKafkaResult<cluster::Model> {
+    cluster::Entity::find_by_id(token)
+        .one(db)
+        .await?
+        .ok_or_else(|| EasyKafkaError::KafkaConnectNotFound(token.to_string()))
+}
+
+/// 读取 topic 保存的 key/value 格式，未配置时按文本处理
+pub async fn find_topic_format(
+    token: &str,
+    topic_name: &str,
+    db: &DatabaseConnection,
+) -> EasyKafkaResult<TopicFormat> {
+    let model = topics::Entity::find()
+        .filter(topics::Column::ClusterId.eq(token))
+        .filter(topics::Column::TopicName.eq(topic_name))
+        .one(db)
+        .await?;
+    let parse = |value: Option<&str>| -> EasyKafkaResult<FormatConfig> {
+        match value {
+            Some(value) => Ok(serde_json::from_str(value)?),
+            None => Ok(FormatConfig::default()),
+        }
+    };
+    match model {
+        Some(model) => Ok(TopicFormat {
+            key: parse(model.key_format.as_deref())?,
+            value: parse(model.value_format.as_deref())?,
+        }),
+        None => Ok(TopicFormat::default()),
+    }
+}
+
+/// 按 topic 保存的格式创建 key/value 编解码器
+pub async fn load_record_codec(
+    token: &str,
+    topic_name: &str,
+    db: &DatabaseConnection,
+) -> EasyKafkaResult<RecordCodec> {
+    let format = find_topic_format(token, topic_name, db).await?;
+    let connect = find_cluster(token, db).await?;
+    build_record_codec(&format, &connect).await
+}
+
+/// 创建 key/value 编解码器，registry 格式使用集群配置的 schema registry
+pub async fn build_record_codec(
+    format: &TopicFormat,
+    connect: &cluster::Model,
+) -> EasyKafkaResult<RecordCodec> {
+    let registry = SchemaRegistryClient::from_cluster(connect)?.map(Arc::new);
+    Ok(RecordCodec {
+        key: build_field_codec("key", &format.key, registry.as_ref()).await?,
+        value: build_field_codec("value", &format.value, registry.as_ref()).await?,
+    })
+}
+
+async fn build_field_codec(
+    field: &str,
+    config: &FormatConfig,
+    registry: Option<&Arc<SchemaRegistryClient>>,
+) -> EasyKafkaResult<FieldCodec> {
+    if config.format != MessageFormat::Registry {
+        // 读取 schema 文件和编译 .proto 文件比较耗时，在阻塞线程中执行
+        let build_config = config.clone();
+        return tokio::task::spawn_blocking(move || FieldCodec::build(&build_config))
+            .await?
+            .map_err(|e| EasyKafkaError::CodecError(format!("{}格式错误: {}", field, e)));
+    }
+    let registry = registry.ok_or_else(|| {
+        EasyKafkaError::CodecError(format!("{}格式错误: 集群未配置schema registry地址", field))
+    })?;
+    let codec = RegistryCodec::new(registry.clone(), config).await?;
+    Ok(FieldCodec::new(
+        MessageFormat::Registry,
+        Box::new(codec),
+        config.fallback,
+    ))
+}
+
+/// 根据集群配置生成各类客户端共用的连接配置
+pub fn create_client_config(connect: &cluster::Model) -> EasyKafkaResult<ClientConfig> {
+    let mut client_config = ClientConfig::new();
+    client_config.set("bootstrap.servers", connect.servers.as_str());
+    for (key, value) in security_properties(connect).map_err(EasyKafkaError::SecurityConfigError)? {
+        client_config.set(key, value);
+    }
+    client_config.set("socket.timeout.ms", connect.timeout.to_string());
+    Ok(client_config)
+}
+
+/// 连接检查使用的客户端上下文，记录 librdkafka 在后台报告的认证和 SSL 错误，
+/// 这类错误不会体现在请求的返回值中，请求只会超时
+#[derive(Default)]
+pub struct DiagnosticContext {
+    security_errors: Mutex<Vec<String>>,
+}
+
+impl ClientContext for DiagnosticContext {
+    fn error(&self, error: KafkaError, reason: &str) {
+        error!("librdkafka: {}: {}", error, reason);
+        if matches!(
+            error.rdkafka_error_code(),
+            Some(
+                RDKafkaErrorCode::Authentication
+                    | RDKafkaErrorCode::SaslAuthenticationFailed
+                    | RDKafkaErrorCode::SSL
+            )
+        ) {
+            self.security_errors
+                .lock()
+                .unwrap()
+                .push(reason.to_string());
+        }
+    }
+}
+
+impl ConsumerContext for DiagnosticContext {}
+
+/// 使用新的客户端检查集群连接，返回 broker 数量，认证或 SSL 握手失败时返回排查提示
+pub fn diagnose_connect(connect: &cluster::Model) -> EasyKafkaResult<Result<usize, String>> {
+    let consumer: BaseConsumer<DiagnosticContext> =
+        create_client_config(connect)?.create_with_context(DiagnosticContext::default())?;
+    let metadata = consumer.fetch_metadata(
+        None,
+        Timeout::from(Duration::from_millis(connect.timeout as u64)),
+    );
+    let result = match metadata {
+        Ok(metadata) => Ok(metadata.brokers().len()),
+        Err(e) => match consumer.context().security_errors.lock().unwrap().last() {
+            Some(reason) => Err(describe_auth_failure(connect, reason)),
+            None => Err(format!("Kafka连接失败: {}", e)),
+        },
+    };
+    Ok(result)
+}
+
+/// 判断是否为 Kafka 或周边组件使用的内部 topic
+pub fn is_internal_topic(topic_name: &str) -> bool {
+    topic_name.starts_with("__") || topic_name.starts_with("_confluent") || topic_name == "_schemas"
+}
+
+/// 获取集群元数据，topic 为空时返回所有 topic
+pub fn fetch_metadata(
+    admin_client: &AdminClient<DefaultClientContext>,
+    topic: Option<&str>,
+    timeout: Duration,
+) -> EasyKafkaResult<Metadata> {
+    Ok(admin_client
+        .inner()
+        .fetch_metadata(topic, Timeout::from(timeout))?)
+}
+
+/// 校验 topic 和分区是否存在，分区为空时返回 topic 的所有分区，校验不通过时返回错误信息
+pub fn resolve_partitions(
+    consumer: &BaseConsumer,
+    topic: &str,
+    partitions: Option<&[i32]>,
+    timeout: Duration,
+) -> EasyKafkaResult<Result<Vec<i32>, String>> {
+    let metadata = consumer.fetch_metadata(Some(topic), Timeout::from(timeout))?;
+    let existing = match metadata
+        .topics()
+        .iter()
+        .find(|item| item.name() == topic && item.error().is_none())
+    {
+        Some(item) => item
+            .partitions()
+            .iter()
+            .map(|item| item.id())
+            .collect::<Vec<_>>(),
+        None => return Ok(Err(format!("topic[{}]不存在", topic))),
+    };
+    match partitions.filter(|item| !item.is_empty()) {
+        Some(partitions) => match partitions.iter().find(|item| !existing.contains(item)) {
+            Some(partition) => Ok(Err(format!("分区[{}]不存在", partition))),
+            None => Ok(Ok(partitions.to_vec())),
+        },
+        None => Ok(Ok(existing)),
+    }
+}
+
+/// 获取分区的水位，返回 (low, high)
+pub fn fetch_watermarks(
+    admin_client: &AdminClient<DefaultClientContext>,
+    topic: &str,
+    partition: i32,
+    timeout: Duration,
+) -> EasyKafkaResult<(i64, i64)> {
+    Ok(admin_client
+        .inner()
+        .fetch_watermarks(topic, partition, Timeout::from(timeout))?)
+}
+
+/// 根据元数据和分区水位构建 topic 信息
+pub fn build_topic_response(
+    admin_client: &AdminClient<DefaultClientContext>,
+    topic: &MetadataTopic,
+    timeout: Duration,
+) -> EasyKafkaResult<TopicResponse> {
+    let mut partitions = Vec::with_capacity(topic.partitions().len());
+    for partition in topic.partitions() {
+        let (low, high) = fetch_watermarks(admin_client, topic.name(), partition.id(), timeout)?;
+        partitions.push(PartitionResponse {
+            id: partition.id(),
+            leader: partition.leader(),
+            replicas: partition.replicas().to_vec(),
+            isr: partition.isr().to_vec(),
+            low_offset: low,
+            high_offset: high,
+            message_count: high - low,
+        });
+    }
+    partitions.sort_by_key(|item| item.id);
+
+    TopicResponseBuilder::default()
+        .name(topic.name().to_string())
+        .internal(is_internal_topic(topic.name()))
+        .partition_count(partitions.len())
+        .replication_factor(
+            partitions
+                .iter()
+                .map(|item| item.replicas.len())
+                .max()
+                .unwrap_or(0),
+        )
+        .message_count(
+            partitions
+                .iter()
+                .map(|item| item.message_count)
+                .sum::<i64>(),
+        )
+        .partitions(partitions)
+        .build()
+        .map_err(|e| {
+            EasyKafkaError::KafkaConnectNotFound(format!("Failed to build response: {:?}", e))
+        })
+}
+
+/// 查询分区中时间戳大于等于 timestamp 的第一条消息的 offset，没有时返回 Offset::End
+pub fn fetch_offsets_for_timestamp(
+    consumer: &BaseConsumer,
+    topic: &str,
+    partitions: &[i32],
+    timestamp: i64,
+    timeout: Duration,
+) -> EasyKafkaResult<HashMap<i32, Offset>> {
+    let mut timestamps = TopicPartitionList::new();
+    for partition in partitions {
+        timestamps.add_partition_offset(topic, *partition, Offset::Offset(timestamp))?;
+    }
+    let offsets = consumer.offsets_for_times(timestamps, Timeout::from(timeout))?;
+    Ok(offsets
+        .elements_for_topic(topic)
+        .iter()
+        .map(|item| (item.partition(), item.offset()))
+        .collect())
+}
+
+/// 按 topic 的格式编码 key/value 后发送单条消息，并等待投递结果
+pub async fn send_message(
+    producer: &FutureProducer,
+    request: &ProduceMessageRequest,
+    codec: &RecordCodec,
+    timeout: Duration,
+) -> DeliveryResult {
+    let key = match encode_field(&codec.key, request.key.as_deref(), "key") {
+        Ok(key) => key,
+        Err(result) => return result,
+    };
+    let value = match encode_field(&codec.value, request.value.as_deref(), "value") {
+        Ok(value) => value,
+        Err(result) => return result,
+    };
+    let mut record = FutureRecord::<[u8], [u8]>::to(&request.topic);
+    if let Some(key) = key.as_deref() {
+        record = record.key(key);
+    }
+    if let Some(value) = value.as_deref() {
+        record = record.payload(value);
+    }
+    if let Some(partition) = request.partition {
+        record = record.partition(partition);
+    }
+    if let Some(timestamp) = request.timestamp {
+        record = record.timestamp(timestamp);
+    }
+    let headers = request
+        .headers
+        .iter()
+        .filter(|item| !item.key.is_empty())
+        .fold(OwnedHeaders::new(), |headers, item| {
+            headers.insert(Header {
+                key: &item.key,
+                value: item.value.as_deref(),
+            })
+        });
+    if headers.count() > 0 {
+        record = record.headers(headers);
+    }
+
+    match producer.send(record, Timeout::from(timeout)).await {
+        Ok(delivery) => DeliveryResult::Delivered {
+            partition: delivery.partition,
+            offset: delivery.offset,
+            timestamp: delivery.timestamp.to_millis(),
+        },
+        Err((e, _)) => DeliveryResult::Failed {
+            code: e
+                .rdkafka_error_code()
+                .map(|code| format!("{:?}", code))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            msg: e.to_string(),
+        },
+    }
+}
+
+fn encode_field(
+    codec: &FieldCodec,
+    text: Option<&str>,
+    field: &str,
+) -> Result<Option<Vec<u8>>, DeliveryResult> {
+    text.map(|text| codec.encode(text))
+        .transpose()
+        .map_err(|e| DeliveryResult::Failed {
+            code: "SerializationError".to_string(),
+            msg: format!("{}编码失败: {}", field, e),
+        })
+}
+
+/// 计算每个分区的起始读取位置，返回 partition -> (start, high)，已读完的分区不会出现在结果中
+pub fn resolve_start_offsets(
+    consumer: &BaseConsumer,
+    topic: &str,
+    partitions: &[i32],
+    position: &StartPosition,
+    limit: usize,
+    timeout: Duration,
+) -> EasyKafkaResult<HashMap<i32, (i64, i64)>> {
+    let timestamp_offsets = match position {
+        StartPosition::Timestamp { timestamp } => {
+            fetch_offsets_for_timestamp(consumer, topic, partitions, *timestamp, timeout)?
+        }
+        _ => HashMap::new(),
+    };
+    let mut result = HashMap::new();
+    for partition in partitions {
+        let (low, high) = consumer.fetch_watermarks(topic, *partition, Timeout::from(timeout))?;
+        let start = match position {
+            StartPosition::Earliest => low,
+            StartPosition::Latest => high - limit as i64,
+            StartPosition::Offset { offset } => *offset,
+            StartPosition::Timestamp { .. } => match timestamp_offsets.get(partition) {
+                Some(Offset::Offset(offset)) => *offset,
+                _ => high,
+            },
+        }
+        .clamp(low, high);
+        if start < high {
+            result.insert(*partition, (start, high));
+        }
+    }
+    Ok(result)
+}
+
+/// 从指定位置读取消息，读到分配时的高水位、匹配到 limit 条或超时后结束
+pub fn poll_records(
+    consumer: &BaseConsumer,
+    topic: &str,
+    offsets: &HashMap<i32, (i64, i64)>,
+    limit: usize,
+    filter: Option<&MessageFilter>,
+    codec: &RecordCodec,
+    timeout: Duration,
+) -> EasyKafkaResult<ConsumeMessagesResponse> {
+    let mut response = ConsumeMessagesResponse::default();
+    if offsets.is_empty() || limit == 0 {
+        return Ok(response);
+    }
+    let mut assignment = TopicPartitionList::new();
+    for (partition, (start, _)) in offsets.iter() {
+        assignment.add_partition_offset(topic, *partition, Offset::Offset(*start))?;
+    }
+    consumer.assign(&assignment)?;
+
+    let mut remaining = offsets
+        .iter()
+        .map(|(partition, (_, high))| (*partition, *high))
+        .collect::<HashMap<_, _>>();
+    let deadline = Instant::now() + timeout;
+    while !remaining.is_empty() && response.records.len() < limit && Instant::now() < deadline {
+        match consumer.poll(Timeout::from(Duration::from_millis(200))) {
+            Some(Ok(message)) => {
+                if let Some(high) = remaining.get(&message.partition()) {
+                    if message.offset() + 1 >= *high {
+                        remaining.remove(&message.partition());
+                    }
+                }
+                let record = to_consumer_record(&message, codec);
+                response.scanned += 1;
+                if filter.is_none_or(|filter| filter.matches(&record)) {
+                    response.matched += 1;
+                    response.records.push(record);
+                }
             }
-            client_config.set("socket.timeout.ms", connect.timeout.to_string());
-            Ok((client_config.create()?, connect))
+            Some(Err(e)) => return Err(e.into()),
+            None => {}
         }
-        None => Err(EasyKafkaError::KafkaConnectNotFound(token.to_string())),
+    }
+    Ok(response)
+}
+
+/// 转换为展示用的消息，key/value 按 topic 的格式解码
+pub fn to_consumer_record<M: Message>(message: &M, codec: &RecordCodec) -> ConsumerRecord {
+    let (timestamp, timestamp_type) = match message.timestamp() {
+        Timestamp::NotAvailable => (None, "NotAvailable"),
+        Timestamp::CreateTime(timestamp) => (Some(timestamp), "CreateTime"),
+        Timestamp::LogAppendTime(timestamp) => (Some(timestamp), "LogAppendTime"),
+    };
+    let headers = message
+        .headers()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|header| RecordHeader {
+                    key: header.key.to_string(),
+                    value: header
+                        .value
+                        .map(|value| String::from_utf8_lossy(value).to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let (key, key_format) = message.key().map(|key| codec.key.decode(key)).unzip();
+    let (value, value_format) = message
+        .payload()
+        .map(|value| codec.value.decode(value))
+        .unzip();
+    ConsumerRecord {
+        topic: message.topic().to_string(),
+        partition: message.partition(),
+        offset: message.offset(),
+        key,
+        value,
+        key_format,
+        value_format,
+        headers,
+        timestamp,
+        timestamp_type: timestamp_type.to_string(),
     }
 }
@@ -1,3 +1,7 @@
+pub mod client_registry;
+pub mod dashboard_infra;
 pub mod executor;
+pub mod group_infra;
 pub mod kafka_infra;
+pub mod schema_registry;
 pub mod sql_infra;
@@ -0,0 +1,578 @@
+use crate::entity::db_entity::cluster;
+use crate::entity::request::topic::FormatConfig;
+use crate::entity::response::registry::{
+    CompatibilityResult, RegisteredSchema, SchemaReference, SchemaType,
+};
+use crate::utils::codec::{AvroCodec, Codec, Deserializer, JsonCodec, ProtobufCodec, Serializer};
+use crate::{EasyKafkaError, EasyKafkaResult};
+use prost_reflect::{DescriptorPool, MessageDescriptor};
+use protox::file::{ChainFileResolver, File, FileResolver, GoogleFileResolver};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::time::Duration;
+
+/// Confluent 格式的 magic byte
+const MAGIC_BYTE: u8 = 0;
+/// registry 中 protobuf schema 编译时使用的文件名
+const MAIN_PROTO: &str = "__registry_main__.proto";
+
+#[derive(Debug, Deserialize)]
+struct RegistryErrorBody {
+    message: String,
+}
+
+/// Confluent Schema Registry 的 REST 客户端，按 id 查询的 schema 会被缓存
+pub struct SchemaRegistryClient {
+    http: Client,
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    schemas: Mutex<HashMap<i32, RegisteredSchema>>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(
+        url: &str,
+        username: Option<String>,
+        password: Option<String>,
+        timeout: Duration,
+    ) -> EasyKafkaResult<Self> {
+        Url::parse(url)
+            .map_err(|e| EasyKafkaError::SchemaRegistryError(format!("地址错误: {}", e)))?;
+        Ok(Self {
+            http: Client::builder().timeout(timeout).build()?,
+            url: url.to_string(),
+            username: username.filter(|item| !item.is_empty()),
+            password,
+            schemas: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 根据集群配置创建客户端，集群未配置 registry 地址时返回 None
+    pub fn from_cluster(connect: &cluster::Model) -> EasyKafkaResult<Option<Self>> {
+        match connect
+            .registry_url
+            .as_deref()
+            .filter(|item| !item.is_empty())
+        {
+            Some(url) => Ok(Some(Self::new(
+                url,
+                connect.registry_username.clone(),
+                connect.registry_password.clone(),
+                Duration::from_millis(connect.timeout as u64),
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn subjects(&self) -> EasyKafkaResult<Vec<String>> {
+        let url = self.endpoint(&["subjects"])?;
+        self.send(self.http.get(url)).await
+    }
+
+    pub async fn versions(&self, subject: &str) -> EasyKafkaResult<Vec<i32>> {
+        let url = self.endpoint(&["subjects", subject, "versions"])?;
+        self.send(self.http.get(url)).await
+    }
+
+    /// 查询 subject 的指定版本，版本为空时查询最新版本
+    pub async fn schema_by_version(
+        &self,
+        subject: &str,
+        version: Option<i32>,
+    ) -> EasyKafkaResult<RegisteredSchema> {
+        let version = version_segment(version);
+        let url = self.endpoint(&["subjects", subject, "versions", &version])?;
+        self.send(self.http.get(url)).await
+    }
+
+    pub async fn schema_by_id(&self, id: i32) -> EasyKafkaResult<RegisteredSchema> {
+        if let Some(schema) = self.schemas.lock().unwrap().get(&id) {
+            return Ok(schema.clone());
+        }
+        let url = self.endpoint(&["schemas", "ids", &id.to_string()])?;
+        let mut schema: RegisteredSchema = self.send(self.http.get(url)).await?;
+        schema.id = id;
+        self.schemas.lock().unwrap().insert(id, schema.clone());
+        Ok(schema)
+    }
+
+    /// 检查 schema 与 subject 的指定版本是否兼容，版本为空时与最新版本比较
+    pub async fn check_compatibility(
+        &self,
+        subject: &str,
+        version: Option<i32>,
+        schema: &str,
+        schema_type: SchemaType,
+    ) -> EasyKafkaResult<CompatibilityResult> {
+        let version = version_segment(version);
+        let mut url =
+            self.endpoint(&["compatibility", "subjects", subject, "versions", &version])?;
+        url.set_query(Some("verbose=true"));
+        let body = json!({ "schema": schema, "schemaType": schema_type });
+        self.send(self.http.post(url).json(&body)).await
+    }
+
+    fn endpoint(&self, segments: &[&str]) -> EasyKafkaResult<Url> {
+        let mut url = Url::parse(&self.url)
+            .map_err(|e| EasyKafkaError::SchemaRegistryError(format!("地址错误: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| EasyKafkaError::SchemaRegistryError(format!("地址错误: {}", self.url)))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> EasyKafkaResult<T> {
+        let request = match self.username.as_deref() {
+            Some(username) => request.basic_auth(username, self.password.as_deref()),
+            None => request,
+        };
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+        let message = match response.json::<RegistryErrorBody>().await {
+            Ok(body) => body.message,
+            Err(_) => status.to_string(),
+        };
+        Err(EasyKafkaError::SchemaRegistryError(format!(
+            "{}: {}",
+            status.as_u16(),
+            message
+        )))
+    }
+
+    /// 根据 schema 创建编解码器，protobuf 按 message index 路径选择消息类型
+    async fn schema_codec(
+        &self,
+        schema: &RegisteredSchema,
+        message: MessageSelector<'_>,
+    ) -> EasyKafkaResult<(Box<dyn Codec>, Vec<i32>)> {
+        match schema.schema_type {
+            SchemaType::Avro => Ok((
+                Box::new(AvroCodec::parse(&schema.schema).map_err(EasyKafkaError::CodecError)?),
+                Vec::new(),
+            )),
+            SchemaType::Json => Ok((Box::new(JsonCodec), Vec::new())),
+            SchemaType::Protobuf => {
+                let mut sources = HashMap::new();
+                self.load_proto_sources(&schema.references, &mut sources)
+                    .await?;
+                let pool = compile_proto(&schema.schema, sources)?;
+                let (descriptor, indexes) = select_message(&pool, message)?;
+                Ok((Box::new(ProtobufCodec::new(descriptor)), indexes))
+            }
+        }
+    }
+
+    /// 递归加载 protobuf schema 引用的其他 subject，返回 import 路径到源码的映射
+    fn load_proto_sources<'a>(
+        &'a self,
+        references: &'a [SchemaReference],
+        sources: &'a mut HashMap<String, String>,
+    ) -> Pin<Box<dyn Future<Output = EasyKafkaResult<()>> + Send + 'a>> {
+        Box::pin(async move {
+            for reference in references {
+                if sources.contains_key(&reference.name) {
+                    continue;
+                }
+                let schema = self
+                    .schema_by_version(&reference.subject, Some(reference.version))
+                    .await?;
+                sources.insert(reference.name.clone(), schema.schema);
+                self.load_proto_sources(&schema.references, sources).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// registry 格式的编解码器，解码时按消息中的 schema id 查询 schema，编码时使用配置的 subject
+pub struct RegistryCodec {
+    client: Arc<SchemaRegistryClient>,
+    writer: Option<RegistryWriter>,
+    readers: Mutex<HashMap<ReaderKey, Arc<dyn Codec>>>,
+}
+
+/// schema id 和 protobuf 的 message index 路径
+type ReaderKey = (i32, Vec<i32>);
+
+struct RegistryWriter {
+    id: i32,
+    schema_type: SchemaType,
+    indexes: Vec<i32>,
+    codec: Box<dyn Codec>,
+}
+
+enum MessageSelector<'a> {
+    Name(Option<&'a str>),
+    Indexes(&'a [i32]),
+}
+
+impl RegistryCodec {
+    pub async fn new(
+        client: Arc<SchemaRegistryClient>,
+        config: &FormatConfig,
+    ) -> EasyKafkaResult<Self> {
+        let writer = match config.subject.as_deref().filter(|item| !item.is_empty()) {
+            Some(subject) => {
+                let schema = client.schema_by_version(subject, config.version).await?;
+                let (codec, indexes) = client
+                    .schema_codec(
+                        &schema,
+                        MessageSelector::Name(config.message_type.as_deref()),
+                    )
+                    .await?;
+                Some(RegistryWriter {
+                    id: schema.id,
+                    schema_type: schema.schema_type,
+                    indexes,
+                    codec,
+                })
+            }
+            None => None,
+        };
+        Ok(Self {
+            client,
+            writer,
+            readers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 在阻塞线程中查询 schema 并创建对应的编解码器
+    fn reader(&self, id: i32, payload: &[u8]) -> Result<(Arc<dyn Codec>, usize), String> {
+        let schema = tauri::async_runtime::block_on(self.client.schema_by_id(id))
+            .map_err(|e| e.to_string())?;
+        let (indexes, consumed) = match schema.schema_type {
+            SchemaType::Protobuf => read_message_indexes(payload)?,
+            _ => (Vec::new(), 0),
+        };
+        let key = (id, indexes);
+        if let Some(codec) = self.readers.lock().unwrap().get(&key) {
+            return Ok((codec.clone(), consumed));
+        }
+        let (codec, _) = tauri::async_runtime::block_on(
+            self.client
+                .schema_codec(&schema, MessageSelector::Indexes(&key.1)),
+        )
+        .map_err(|e| e.to_string())?;
+        let codec: Arc<dyn Codec> = Arc::from(codec);
+        self.readers.lock().unwrap().insert(key, codec.clone());
+        Ok((codec, consumed))
+    }
+}
+
+impl Deserializer for RegistryCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        if bytes.len() < 5 || bytes[0] != MAGIC_BYTE {
+            return Err("不是schema registry格式的消息".to_string());
+        }
+        let id = i32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+        let (codec, consumed) = self.reader(id, &bytes[5..])?;
+        codec.deserialize(&bytes[5 + consumed..])
+    }
+}
+
+impl Serializer for RegistryCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        let writer = self
+            .writer
+            .as_ref()
+            .ok_or_else(|| "未配置subject，无法按schema registry格式编码".to_string())?;
+        let mut bytes = vec![MAGIC_BYTE];
+        bytes.extend_from_slice(&writer.id.to_be_bytes());
+        if writer.schema_type == SchemaType::Protobuf {
+            write_message_indexes(&writer.indexes, &mut bytes);
+        }
+        bytes.extend(writer.codec.serialize(text)?);
+        Ok(bytes)
+    }
+}
+
+/// 从内存中的源码解析 import，registry 引用的 schema 按引用名称查找
+struct SourceResolver(HashMap<String, String>);
+
+impl FileResolver for SourceResolver {
+    fn open_file(&self, name: &str) -> Result<File, protox::Error> {
+        match self.0.get(name) {
+            Some(source) => File::from_source(name, source),
+            None => Err(protox::Error::file_not_found(name)),
+        }
+    }
+}
+
+fn compile_proto(
+    main: &str,
+    mut sources: HashMap<String, String>,
+) -> EasyKafkaResult<DescriptorPool> {
+    sources.insert(MAIN_PROTO.to_string(), main.to_string());
+    let mut resolver = ChainFileResolver::new();
+    resolver.add(SourceResolver(sources));
+    resolver.add(GoogleFileResolver::new());
+    let mut compiler = protox::Compiler::with_file_resolver(resolver);
+    compiler.include_imports(true);
+    compiler
+        .open_file(MAIN_PROTO)
+        .map_err(|e| EasyKafkaError::CodecError(format!("protobuf schema错误: {}", e)))?;
+    Ok(compiler.descriptor_pool())
+}
+
+/// 选择 schema 中的消息类型，未指定名称时使用第一个消息
+fn select_message(
+    pool: &DescriptorPool,
+    selector: MessageSelector,
+) -> EasyKafkaResult<(MessageDescriptor, Vec<i32>)> {
+    let file = pool
+        .get_file_by_name(MAIN_PROTO)
+        .ok_or_else(|| EasyKafkaError::CodecError("protobuf schema错误".to_string()))?;
+    let indexes = match selector {
+        MessageSelector::Name(Some(name)) => find_message_path(file.messages(), name)
+            .ok_or_else(|| EasyKafkaError::CodecError(format!("消息类型[{}]不存在", name)))?,
+        MessageSelector::Name(None) => vec![0],
+        MessageSelector::Indexes(indexes) => indexes.to_vec(),
+    };
+    let (first, rest) = indexes
+        .split_first()
+        .ok_or_else(|| EasyKafkaError::CodecError("message index 为空".to_string()))?;
+    let mut message = file.messages().nth(*first as usize);
+    for index in rest {
+        message = message.and_then(|item| item.child_messages().nth(*index as usize));
+    }
+    match message {
+        Some(message) => Ok((message, indexes)),
+        None => Err(EasyKafkaError::CodecError(format!(
+            "message index {:?} 不存在",
+            indexes
+        ))),
+    }
+}
+
+fn find_message_path(
+    messages: impl Iterator<Item = MessageDescriptor>,
+    name: &str,
+) -> Option<Vec<i32>> {
+    for (index, message) in messages.enumerate() {
+        if message.full_name() == name {
+            return Some(vec![index as i32]);
+        }
+        if let Some(mut path) = find_message_path(message.child_messages(), name) {
+            path.insert(0, index as i32);
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn version_segment(version: Option<i32>) -> String {
+    version
+        .map(|item| item.to_string())
+        .unwrap_or_else(|| "latest".to_string())
+}
+
+/// 读取 protobuf 的 message index 数组，返回下标路径和占用的字节数，只有一个 0 时简写为 [0]
+fn read_message_indexes(bytes: &[u8]) -> Result<(Vec<i32>, usize), String> {
+    let mut position = 0;
+    let count = read_zigzag(bytes, &mut position)?;
+    if count == 0 {
+        return Ok((vec![0], position));
+    }
+    // 每个下标至少占一个字节，数量不能超过剩余的字节数
+    if count < 0 || count as u64 > (bytes.len() - position) as u64 {
+        return Err("message index 格式错误".to_string());
+    }
+    let mut indexes = Vec::new();
+    for _ in 0..count {
+        indexes.push(read_zigzag(bytes, &mut position)? as i32);
+    }
+    Ok((indexes, position))
+}
+
+fn write_message_indexes(indexes: &[i32], bytes: &mut Vec<u8>) {
+    if indexes == [0] {
+        bytes.push(0);
+        return;
+    }
+    write_zigzag(indexes.len() as i64, bytes);
+    for index in indexes {
+        write_zigzag(*index as i64, bytes);
+    }
+}
+
+fn read_zigzag(bytes: &[u8], position: &mut usize) -> Result<i64, String> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*position)
+            .ok_or_else(|| "message index 格式错误".to_string())?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+    Err("message index 格式错误".to_string())
+}
+
+fn write_zigzag(value: i64, bytes: &mut Vec<u8>) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+#[cfg(test)]
+mod schema_registry_test {
+    use crate::entity::request::topic::FormatConfig;
+    use crate::entity::response::registry::SchemaType;
+    use crate::infra::schema_registry::{
+        read_message_indexes, write_message_indexes, RegistryCodec, SchemaRegistryClient,
+    };
+    use crate::utils::codec::{Deserializer, Serializer};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::time::Duration;
+    use wiremock::matchers::{basic_auth, body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const JSON_SCHEMA: &str = r#"{"type":"object","properties":{"id":{"type":"integer"}}}"#;
+
+    async fn client(server: &MockServer) -> Arc<SchemaRegistryClient> {
+        Arc::new(
+            SchemaRegistryClient::new(
+                &server.uri(),
+                Some("user".to_string()),
+                Some("secret".to_string()),
+                Duration::from_secs(5),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn subjects_test() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/subjects"))
+            .and(basic_auth("user", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(["orders-value"])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/orders-value/versions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([1, 2])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/missing/versions"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(
+                json!({"error_code": 40401, "message": "Subject 'missing' not found."}),
+            ))
+            .mount(&server)
+            .await;
+
+        let client = client(&server).await;
+        assert_eq!(client.subjects().await.unwrap(), vec!["orders-value"]);
+        assert_eq!(client.versions("orders-value").await.unwrap(), vec![1, 2]);
+        let error = client.versions("missing").await.unwrap_err().to_string();
+        assert!(error.contains("404") && error.contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn compatibility_test() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/compatibility/subjects/orders-value/versions/latest"))
+            .and(body_partial_json(json!({"schemaType": "JSON"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    json!({"is_compatible": false, "messages": ["property removed"]}),
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let result = client(&server)
+            .await
+            .check_compatibility("orders-value", None, JSON_SCHEMA, SchemaType::Json)
+            .await
+            .unwrap();
+        assert!(!result.is_compatible);
+        assert_eq!(result.messages, vec!["property removed"]);
+    }
+
+    #[tokio::test]
+    async fn wire_format_test() {
+        let server = MockServer::start().await;
+        let schema = json!({
+            "subject": "orders-value",
+            "id": 7,
+            "version": 1,
+            "schemaType": "JSON",
+            "schema": JSON_SCHEMA,
+        });
+        Mock::given(method("GET"))
+            .and(path("/subjects/orders-value/versions/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(schema.clone()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(schema))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = FormatConfig {
+            subject: Some("orders-value".to_string()),
+            ..Default::default()
+        };
+        let codec = RegistryCodec::new(client(&server).await, &config)
+            .await
+            .unwrap();
+        let bytes = codec.serialize(r#"{"id": 1}"#).unwrap();
+        assert_eq!(&bytes[..5], &[0, 0, 0, 0, 7]);
+
+        // 解码时会阻塞等待 registry 的响应，需要在阻塞线程中执行
+        let (text, again) = tokio::task::spawn_blocking(move || {
+            (codec.deserialize(&bytes), codec.deserialize(&bytes))
+        })
+        .await
+        .unwrap();
+        let expected = json!({"id": 1});
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&text.unwrap()).unwrap(),
+            expected
+        );
+        assert!(again.is_ok());
+    }
+
+    #[test]
+    fn message_indexes_test() {
+        for indexes in [vec![0], vec![1], vec![0, 2, 1]] {
+            let mut bytes = Vec::new();
+            write_message_indexes(&indexes, &mut bytes);
+            bytes.push(0xff);
+            let (decoded, consumed) = read_message_indexes(&bytes).unwrap();
+            assert_eq!(decoded, indexes);
+            assert_eq!(consumed, bytes.len() - 1);
+        }
+        assert_eq!(read_message_indexes(&[0]).unwrap(), (vec![0], 1));
+        assert!(read_message_indexes(&[0x80]).is_err());
+        // magic byte 和 protobuf schema id 之后的 0x01 解码为 -1
+        let bytes = [0, 0, 0, 0, 7, 0x01, 0x08];
+        assert!(read_message_indexes(&bytes[5..]).is_err());
+        // 数量超过剩余字节数
+        assert!(read_message_indexes(&[0x06, 0x02]).is_err());
+        assert!(read_message_indexes(&[0xfe, 0xff, 0xff, 0xff, 0x0f]).is_err());
+    }
+}
@@ -17,7 +17,7 @@ pub enum EasyKafkaError {
     KafkaConnectNotFound(String),
 
     #[error("Other Error: {0}")]
-    StdError(#[from] Box<dyn std::error::Error>),
+    StdError(#[from] Box<dyn std::error::Error + Send + Sync>),
 
     #[error("Toml Error: {0}")]
     TomlError(#[from] toml::de::Error),
@@ -27,6 +27,24 @@ pub enum EasyKafkaError {
 
     #[error("FlexiLogger Error: {0}")]
     FlexiLoggerError(#[from] flexi_logger::FlexiLoggerError),
+
+    #[error("Task Error: {0}")]
+    TaskError(#[from] tokio::task::JoinError),
+
+    #[error("Invalid Param: {0}")]
+    InvalidParam(String),
+
+    #[error("Codec Error: {0}")]
+    CodecError(String),
+
+    #[error("HTTP Error: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    #[error("Schema Registry Error: {0}")]
+    SchemaRegistryError(String),
+
+    #[error("Security Config Error: {0}")]
+    SecurityConfigError(String),
 }
 
 impl Into<InvokeError> for EasyKafkaError {
@@ -1,5 +1,9 @@
 use flexi_logger::{DeferredNow, Logger, Record};
+use std::time::Duration;
 
+use crate::infra::client_registry::ClientRegistry;
+use crate::infra::executor::{JobExecutor, TailSessions};
+use crate::infra::sql_infra::get_connect;
 use crate::kafka_error::EasyKafkaError;
 
 pub(crate) mod config;
@@ -8,6 +12,7 @@ pub(crate) mod handles;
 pub(crate) mod infra;
 pub(crate) mod kafka_error;
 pub(crate) mod mcp;
+pub(crate) mod migration;
 pub(crate) mod utils;
 
 pub type EasyKafkaResult<T> = Result<T, EasyKafkaError>;
@@ -23,8 +28,20 @@ pub async fn run() -> EasyKafkaResult<()> {
         .write_mode(flexi_logger::WriteMode::Direct)
         .start()?;
 
+    // 所有命令共用的数据库连接池
+    let db = get_connect(&config.database).await?;
+    migration::migrate(&db, &config.database).await?;
+
+    // Kafka 客户端缓存
+    let clients = ClientRegistry::new(Duration::from_secs(config.client.idle_ttl));
+    clients.start_sweeper();
+
     tauri::Builder::default()
         .manage(config)
+        .manage(db)
+        .manage(clients)
+        .manage(JobExecutor::default())
+        .manage(TailSessions::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_http::init())
         .invoke_handler(tauri::generate_handler![
@@ -35,6 +52,37 @@ pub async fn run() -> EasyKafkaResult<()> {
             handles::cluster_handles::delete_cluster,
             handles::topic_handles::load_topic_config_template,
             handles::topic_handles::create_topic,
+            handles::topic_handles::topic_list,
+            handles::topic_handles::topic_detail,
+            handles::topic_handles::delete_topics,
+            handles::topic_handles::topic_protect,
+            handles::topic_handles::topic_format,
+            handles::topic_handles::update_topic_format,
+            handles::topic_handles::describe_topic_config,
+            handles::topic_handles::alter_topic_config,
+            handles::topic_handles::increase_partitions,
+            handles::topic_handles::delete_records,
+            handles::producer_handles::produce_message,
+            handles::producer_handles::produce_batch,
+            handles::producer_handles::produce_file,
+            handles::job_handles::cancel_job,
+            handles::registry_handles::registry_subjects,
+            handles::registry_handles::registry_versions,
+            handles::registry_handles::registry_schema,
+            handles::registry_handles::registry_check_compatibility,
+            handles::consumer_group_handles::consumer_group_list,
+            handles::consumer_group_handles::consumer_group_detail,
+            handles::consumer_group_handles::reset_group_offsets,
+            handles::consumer_group_handles::offset_backup_list,
+            handles::consumer_group_handles::restore_group_offsets,
+            handles::consumer_group_handles::delete_consumer_groups,
+            handles::consumer_group_handles::delete_group_offsets,
+            handles::consumer_handles::consume_messages,
+            handles::consumer_handles::search_topic,
+            handles::consumer_handles::tail_topic,
+            handles::consumer_handles::tail_pause,
+            handles::consumer_handles::tail_resume,
+            handles::consumer_handles::tail_stop,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+/// cluster 和 topics 最早的表结构，与早期版本 data.db 中的结构一致
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Cluster::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Cluster::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Cluster::Servers).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(Cluster::ClusterName)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Cluster::Username).string_len(32).null())
+                    .col(ColumnDef::new(Cluster::Password).string_len(32).null())
+                    .col(ColumnDef::new(Cluster::Timeout).integer().not_null())
+                    .col(
+                        ColumnDef::new(Cluster::Ssl)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Cluster::Sasl).string().null())
+                    .col(
+                        ColumnDef::new(Cluster::Connected)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(Topics::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Topics::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Topics::TopicName).string_len(64).not_null())
+                    .col(ColumnDef::new(Topics::Partition).integer().not_null())
+                    .col(ColumnDef::new(Topics::Replica).integer().not_null())
+                    .col(ColumnDef::new(Topics::Properties).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Topics::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Cluster::Table).to_owned())
+            .await
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Cluster {
+    Table,
+    Id,
+    Servers,
+    ClusterName,
+    Username,
+    Password,
+    Timeout,
+    Ssl,
+    Sasl,
+    Connected,
+}
+
+#[derive(DeriveIden)]
+enum Topics {
+    Table,
+    Id,
+    TopicName,
+    Partition,
+    Replica,
+    Properties,
+}
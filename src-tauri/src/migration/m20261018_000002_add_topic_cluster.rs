@@ -0,0 +1,48 @@
+use super::add_column_if_missing;
+use sea_orm_migration::prelude::*;
+
+/// topic 记录所属的集群和删除保护标记
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_column_if_missing(
+            manager,
+            Topics::Table,
+            ColumnDef::new(Topics::ClusterId).string().null(),
+        )
+        .await?;
+        add_column_if_missing(
+            manager,
+            Topics::Table,
+            ColumnDef::new(Topics::Protected)
+                .boolean()
+                .not_null()
+                .default(false),
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Topics::Protected, Topics::ClusterId] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Topics::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Topics {
+    Table,
+    ClusterId,
+    Protected,
+}
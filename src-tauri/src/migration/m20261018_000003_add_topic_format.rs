@@ -0,0 +1,45 @@
+use super::add_column_if_missing;
+use sea_orm_migration::prelude::*;
+
+/// topic 的 key/value 编解码格式
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_column_if_missing(
+            manager,
+            Topics::Table,
+            ColumnDef::new(Topics::KeyFormat).text().null(),
+        )
+        .await?;
+        add_column_if_missing(
+            manager,
+            Topics::Table,
+            ColumnDef::new(Topics::ValueFormat).text().null(),
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Topics::ValueFormat, Topics::KeyFormat] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Topics::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Topics {
+    Table,
+    KeyFormat,
+    ValueFormat,
+}
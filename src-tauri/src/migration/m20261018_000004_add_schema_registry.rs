@@ -0,0 +1,51 @@
+use super::add_column_if_missing;
+use sea_orm_migration::prelude::*;
+
+/// 集群的 schema registry 地址和认证信息
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::RegistryUrl,
+            Cluster::RegistryUsername,
+            Cluster::RegistryPassword,
+        ] {
+            add_column_if_missing(
+                manager,
+                Cluster::Table,
+                ColumnDef::new(column).string().null(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::RegistryPassword,
+            Cluster::RegistryUsername,
+            Cluster::RegistryUrl,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Cluster::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Cluster {
+    Table,
+    RegistryUrl,
+    RegistryUsername,
+    RegistryPassword,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+/// 重置消费者组位移前的备份
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OffsetBackups::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OffsetBackups::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OffsetBackups::ClusterId).string().not_null())
+                    .col(ColumnDef::new(OffsetBackups::GroupId).string().not_null())
+                    .col(ColumnDef::new(OffsetBackups::Topic).string().not_null())
+                    .col(ColumnDef::new(OffsetBackups::Offsets).text().not_null())
+                    .col(
+                        ColumnDef::new(OffsetBackups::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OffsetBackups::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OffsetBackups {
+    Table,
+    Id,
+    ClusterId,
+    GroupId,
+    Topic,
+    Offsets,
+    CreatedAt,
+}
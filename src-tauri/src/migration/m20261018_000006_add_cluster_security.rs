@@ -0,0 +1,67 @@
+use super::add_column_if_missing;
+use sea_orm_migration::prelude::*;
+
+/// 集群的安全协议和 SSL 证书配置
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::SecurityProtocol,
+            Cluster::SslCaLocation,
+            Cluster::SslCertificateLocation,
+            Cluster::SslKeyLocation,
+            Cluster::SslKeyPassword,
+        ] {
+            add_column_if_missing(
+                manager,
+                Cluster::Table,
+                ColumnDef::new(column).string().null(),
+            )
+            .await?;
+        }
+        add_column_if_missing(
+            manager,
+            Cluster::Table,
+            ColumnDef::new(Cluster::SslVerifyHostname)
+                .boolean()
+                .not_null()
+                .default(true),
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::SslVerifyHostname,
+            Cluster::SslKeyPassword,
+            Cluster::SslKeyLocation,
+            Cluster::SslCertificateLocation,
+            Cluster::SslCaLocation,
+            Cluster::SecurityProtocol,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Cluster::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Cluster {
+    Table,
+    SecurityProtocol,
+    SslCaLocation,
+    SslCertificateLocation,
+    SslKeyLocation,
+    SslKeyPassword,
+    SslVerifyHostname,
+}
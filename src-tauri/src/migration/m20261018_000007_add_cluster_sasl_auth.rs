@@ -0,0 +1,63 @@
+use super::add_column_if_missing;
+use sea_orm_migration::prelude::*;
+
+/// 集群的 Kerberos 和 OAuth/OIDC 认证配置
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::KerberosKeytab,
+            Cluster::KerberosPrincipal,
+            Cluster::KerberosServiceName,
+            Cluster::OauthTokenEndpoint,
+            Cluster::OauthClientId,
+            Cluster::OauthClientSecret,
+            Cluster::OauthScope,
+        ] {
+            add_column_if_missing(
+                manager,
+                Cluster::Table,
+                ColumnDef::new(column).string().null(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Cluster::OauthScope,
+            Cluster::OauthClientSecret,
+            Cluster::OauthClientId,
+            Cluster::OauthTokenEndpoint,
+            Cluster::KerberosServiceName,
+            Cluster::KerberosPrincipal,
+            Cluster::KerberosKeytab,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Cluster::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Cluster {
+    Table,
+    KerberosKeytab,
+    KerberosPrincipal,
+    KerberosServiceName,
+    OauthTokenEndpoint,
+    OauthClientId,
+    OauthClientSecret,
+    OauthScope,
+}
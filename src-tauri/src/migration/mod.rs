@@ -0,0 +1,214 @@
+use crate::config::DatabaseConfig;
+use crate::EasyKafkaResult;
+use log::info;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use sea_orm_migration::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod m20261018_000001_create_base_tables;
+mod m20261018_000002_add_topic_cluster;
+mod m20261018_000003_add_topic_format;
+mod m20261018_000004_add_schema_registry;
+mod m20261018_000005_create_offset_backups;
+mod m20261018_000006_add_cluster_security;
+mod m20261018_000007_add_cluster_sasl_auth;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20261018_000001_create_base_tables::Migration),
+            Box::new(m20261018_000002_add_topic_cluster::Migration),
+            Box::new(m20261018_000003_add_topic_format::Migration),
+            Box::new(m20261018_000004_add_schema_registry::Migration),
+            Box::new(m20261018_000005_create_offset_backups::Migration),
+            Box::new(m20261018_000006_add_cluster_security::Migration),
+            Box::new(m20261018_000007_add_cluster_sasl_auth::Migration),
+        ]
+    }
+}
+
+/// 启动时执行未完成的迁移，已有数据的数据库在迁移前先备份
+pub async fn migrate(db: &DatabaseConnection, config: &DatabaseConfig) -> EasyKafkaResult<()> {
+    let pending = Migrator::get_pending_migrations(db).await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let manager = SchemaManager::new(db);
+    if manager.has_table("cluster").await? {
+        if let Some(path) = database_path(&config.url) {
+            let backup = format!("{}.{}.bak", path, backup_suffix());
+            db.execute_unprepared(&format!("VACUUM INTO '{}'", backup.replace('\'', "''")))
+                .await?;
+            info!("database backup: {}", backup);
+        }
+    }
+    info!("apply {} database migrations", pending.len());
+    Migrator::up(db, None).await?;
+    Ok(())
+}
+
+/// 早期版本的数据库可能已经手动加过列，加列前先检查，已存在时跳过
+async fn add_column_if_missing(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden,
+    column: &mut ColumnDef,
+) -> Result<(), DbErr> {
+    let table = table.into_iden();
+    if manager
+        .has_column(table.to_string(), column.get_column_name())
+        .await?
+    {
+        return Ok(());
+    }
+    manager
+        .alter_table(Table::alter().table(table).add_column(column).to_owned())
+        .await
+}
+
+/// 从连接地址中取出数据库文件路径，内存数据库返回 None
+fn database_path(url: &str) -> Option<String> {
+    let path = url
+        .trim_start_matches("sqlite://")
+        .trim_start_matches("sqlite:");
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path == ":memory:" {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+fn backup_suffix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod migration_test {
+    use crate::config::DatabaseConfig;
+    use crate::migration::{database_path, migrate, Migrator};
+    use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+    use sea_orm_migration::{MigratorTrait, SchemaManager};
+
+    /// 最早版本 data.db 中的表结构
+    const BASE_SCHEMA: [&str; 2] = [
+        "CREATE TABLE `topics` (`id` INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT, \
+         `topic_name` VARCHAR(64) NOT NULL, `partition` INT NOT NULL, `replica` INT NOT NULL, \
+         `properties` TEXT(512) NULL)",
+        "CREATE TABLE \"cluster\" (`id` VARCHAR NOT NULL PRIMARY KEY, \
+         `servers` VARCHAR(64) NOT NULL, `cluster_name` VARCHAR(32) NOT NULL, \
+         `username` VARCHAR(32) NULL, `password` VARCHAR(32) NULL, `timeout` INT NOT NULL, \
+         `ssl` BOOLEAN NOT NULL DEFAULT false, `sasl` VARCHAR NULL, \
+         `connected` BOOLEAN NOT NULL DEFAULT false)",
+    ];
+
+    fn database_config(url: &str) -> DatabaseConfig {
+        DatabaseConfig {
+            url: url.to_string(),
+            max_connections: 1,
+            min_connections: 1,
+            connect_timeout: 5,
+            idle_timeout: 60,
+            max_lifetime: 60,
+            sqlx_logging: false,
+        }
+    }
+
+    async fn connect(url: &str) -> DatabaseConnection {
+        let mut options = ConnectOptions::new(url);
+        options.max_connections(1).min_connections(1);
+        Database::connect(options).await.unwrap()
+    }
+
+    async fn assert_latest(db: &DatabaseConnection) {
+        let manager = SchemaManager::new(db);
+        assert!(manager.has_column("topics", "cluster_id").await.unwrap());
+        assert!(manager.has_column("topics", "value_format").await.unwrap());
+        assert!(manager.has_column("cluster", "registry_url").await.unwrap());
+        assert!(manager.has_table("offset_backups").await.unwrap());
+        assert!(manager
+            .has_column("cluster", "oauth_token_endpoint")
+            .await
+            .unwrap());
+        assert!(Migrator::get_pending_migrations(db)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn fresh_install_test() {
+        let url = "sqlite::memory:";
+        let db = connect(url).await;
+        migrate(&db, &database_config(url)).await.unwrap();
+        assert_latest(&db).await;
+        // 再次启动时没有需要执行的迁移
+        migrate(&db, &database_config(url)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn upgrade_test() {
+        let path = std::env::temp_dir().join(format!("easy-kafka-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        let db = connect(&url).await;
+        for sql in BASE_SCHEMA {
+            db.execute_unprepared(sql).await.unwrap();
+        }
+        db.execute_unprepared(
+            "INSERT INTO cluster (id, servers, cluster_name, timeout) \
+             VALUES ('local', 'localhost:9092', 'local', 5000)",
+        )
+        .await
+        .unwrap();
+        // 早期版本手动加过的列
+        db.execute_unprepared("ALTER TABLE topics ADD COLUMN cluster_id VARCHAR NULL")
+            .await
+            .unwrap();
+
+        migrate(&db, &database_config(&url)).await.unwrap();
+        assert_latest(&db).await;
+        let count = db
+            .query_one(sea_orm::Statement::from_string(
+                db.get_database_backend(),
+                "SELECT count(*) AS count FROM cluster",
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get::<i64>("", "count")
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let backups = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.starts_with(&path.file_name().unwrap().to_string_lossy().to_string())
+                    && name.ends_with(".bak")
+            })
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+        for file in backups.iter().chain([&path]) {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    #[test]
+    fn database_path_test() {
+        assert_eq!(
+            database_path("sqlite:./config/data.db?mode=rwc").as_deref(),
+            Some("./config/data.db")
+        );
+        assert_eq!(
+            database_path("sqlite:///tmp/data.db").as_deref(),
+            Some("/tmp/data.db")
+        );
+        assert_eq!(database_path("sqlite::memory:"), None);
+    }
+}
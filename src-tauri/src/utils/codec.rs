@@ -0,0 +1,403 @@
+//! 消息 key/value 的编解码，按 topic 配置的格式在消息字节和展示文本之间转换
+//!
+//! 结构化格式（JSON、MessagePack、Avro、Protobuf）统一展示为格式化后的 JSON，
+//! 发送消息时也以 JSON 文本作为输入。
+
+use crate::entity::request::topic::{BinaryEncoding, FormatConfig, MessageFormat};
+use apache_avro::{from_avro_datum, to_avro_datum, Schema};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde_json::Value;
+use std::path::Path;
+
+/// 把消息字节解码为展示文本
+pub trait Deserializer: Send + Sync {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String>;
+}
+
+/// 把用户输入的文本编码为消息字节
+pub trait Serializer: Send + Sync {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String>;
+}
+
+pub trait Codec: Deserializer + Serializer {}
+
+impl<T: Deserializer + Serializer> Codec for T {}
+
+pub struct TextCodec;
+
+impl Deserializer for TextCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        std::str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Serializer for TextCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        Ok(text.as_bytes().to_vec())
+    }
+}
+
+pub struct JsonCodec;
+
+impl Deserializer for JsonCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        let value: Value = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+        to_pretty_json(&value)
+    }
+}
+
+impl Serializer for JsonCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(&parse_json(text)?).map_err(|e| e.to_string())
+    }
+}
+
+pub struct MessagePackCodec;
+
+impl Deserializer for MessagePackCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        let value: Value = rmp_serde::from_slice(bytes).map_err(|e| e.to_string())?;
+        to_pretty_json(&value)
+    }
+}
+
+impl Serializer for MessagePackCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        rmp_serde::to_vec(&parse_json(text)?).map_err(|e| e.to_string())
+    }
+}
+
+/// 不带 schema 头的 Avro 单条数据，schema 由用户提供
+pub struct AvroCodec {
+    schema: Schema,
+}
+
+impl AvroCodec {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取schema文件[{}]失败: {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(schema: &str) -> Result<Self, String> {
+        let schema = Schema::parse_str(schema).map_err(|e| format!("Avro schema错误: {}", e))?;
+        Ok(Self { schema })
+    }
+}
+
+impl Deserializer for AvroCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        let mut reader = bytes;
+        let value = from_avro_datum(&self.schema, &mut reader, None).map_err(|e| e.to_string())?;
+        let value = Value::try_from(value).map_err(|e| e.to_string())?;
+        to_pretty_json(&value)
+    }
+}
+
+impl Serializer for AvroCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        let value = apache_avro::to_value(parse_json(text)?)
+            .and_then(|value| value.resolve(&self.schema))
+            .map_err(|e| e.to_string())?;
+        to_avro_datum(&self.schema, value).map_err(|e| e.to_string())
+    }
+}
+
+pub struct ProtobufCodec {
+    descriptor: MessageDescriptor,
+}
+
+impl ProtobufCodec {
+    /// 加载 .proto 文件或 descriptor set，.proto 的 import 从文件所在目录查找
+    pub fn load(path: &str, message_type: &str) -> Result<Self, String> {
+        let pool = if path.ends_with(".proto") {
+            let include = Path::new(path).parent().unwrap_or(Path::new("."));
+            let files = protox::compile([path], [include])
+                .map_err(|e| format!("编译proto文件[{}]失败: {}", path, e))?;
+            DescriptorPool::from_file_descriptor_set(files).map_err(|e| e.to_string())?
+        } else {
+            let bytes = std::fs::read(path)
+                .map_err(|e| format!("读取descriptor文件[{}]失败: {}", path, e))?;
+            DescriptorPool::decode(bytes.as_slice()).map_err(|e| e.to_string())?
+        };
+        let descriptor = pool
+            .get_message_by_name(message_type)
+            .ok_or_else(|| format!("消息类型[{}]不存在", message_type))?;
+        Ok(Self::new(descriptor))
+    }
+
+    pub fn new(descriptor: MessageDescriptor) -> Self {
+        Self { descriptor }
+    }
+}
+
+impl Deserializer for ProtobufCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        let message =
+            DynamicMessage::decode(self.descriptor.clone(), bytes).map_err(|e| e.to_string())?;
+        serde_json::to_string_pretty(&message).map_err(|e| e.to_string())
+    }
+}
+
+impl Serializer for ProtobufCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let message = DynamicMessage::deserialize(self.descriptor.clone(), &mut deserializer)
+            .map_err(|e| e.to_string())?;
+        deserializer.end().map_err(|e| e.to_string())?;
+        Ok(message.encode_to_vec())
+    }
+}
+
+pub struct HexCodec;
+
+impl Deserializer for HexCodec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        Ok(hex::encode(bytes))
+    }
+}
+
+impl Serializer for HexCodec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        hex::decode(text.trim()).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Base64Codec;
+
+impl Deserializer for Base64Codec {
+    fn deserialize(&self, bytes: &[u8]) -> Result<String, String> {
+        Ok(STANDARD.encode(bytes))
+    }
+}
+
+impl Serializer for Base64Codec {
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, String> {
+        STANDARD.decode(text.trim()).map_err(|e| e.to_string())
+    }
+}
+
+/// 单个字段（key 或 value）的编解码器，解码失败时退回 hex 或 base64
+pub struct FieldCodec {
+    format: MessageFormat,
+    codec: Box<dyn Codec>,
+    fallback: BinaryEncoding,
+}
+
+impl Default for FieldCodec {
+    fn default() -> Self {
+        Self {
+            format: MessageFormat::Text,
+            codec: Box::new(TextCodec),
+            fallback: BinaryEncoding::Hex,
+        }
+    }
+}
+
+impl FieldCodec {
+    pub fn new(format: MessageFormat, codec: Box<dyn Codec>, fallback: BinaryEncoding) -> Self {
+        Self {
+            format,
+            codec,
+            fallback,
+        }
+    }
+
+    /// 创建本地格式的编解码器，registry 格式需要集群的 registry 配置，由 kafka_infra 创建
+    pub fn build(config: &FormatConfig) -> Result<Self, String> {
+        let codec: Box<dyn Codec> = match config.format {
+            MessageFormat::Text => Box::new(TextCodec),
+            MessageFormat::Json => Box::new(JsonCodec),
+            MessageFormat::MessagePack => Box::new(MessagePackCodec),
+            MessageFormat::Avro => {
+                let path = config
+                    .schema_path
+                    .as_deref()
+                    .ok_or_else(|| "Avro格式需要指定schema文件".to_string())?;
+                Box::new(AvroCodec::load(path)?)
+            }
+            MessageFormat::Protobuf => {
+                let path = config
+                    .schema_path
+                    .as_deref()
+                    .ok_or_else(|| "Protobuf格式需要指定proto文件".to_string())?;
+                let message_type = config
+                    .message_type
+                    .as_deref()
+                    .ok_or_else(|| "Protobuf格式需要指定消息类型".to_string())?;
+                Box::new(ProtobufCodec::load(path, message_type)?)
+            }
+            MessageFormat::Hex => Box::new(HexCodec),
+            MessageFormat::Base64 => Box::new(Base64Codec),
+            MessageFormat::Registry => {
+                return Err("registry格式需要在集群中配置schema registry地址".to_string())
+            }
+        };
+        Ok(Self {
+            format: config.format,
+            codec,
+            fallback: config.fallback,
+        })
+    }
+
+    /// 解码消息字节，返回展示文本和实际使用的格式
+    pub fn decode(&self, bytes: &[u8]) -> (String, MessageFormat) {
+        match self.codec.deserialize(bytes) {
+            Ok(text) => (text, self.format),
+            Err(_) => match self.fallback {
+                BinaryEncoding::Hex => (hex::encode(bytes), MessageFormat::Hex),
+                BinaryEncoding::Base64 => (STANDARD.encode(bytes), MessageFormat::Base64),
+            },
+        }
+    }
+
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, String> {
+        self.codec.serialize(text)
+    }
+}
+
+/// topic 的 key 和 value 编解码器
+#[derive(Default)]
+pub struct RecordCodec {
+    pub key: FieldCodec,
+    pub value: FieldCodec,
+}
+
+fn parse_json(text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).map_err(|e| format!("JSON格式错误: {}", e))
+}
+
+fn to_pretty_json(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod codec_test {
+    use crate::entity::request::topic::{FormatConfig, MessageFormat};
+    use crate::utils::codec::{FieldCodec, Serializer, TextCodec};
+
+    fn codec(format: MessageFormat) -> FieldCodec {
+        FieldCodec::build(&FormatConfig {
+            format,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let json = r#"{"id":1,"tags":["a","b"]}"#;
+        for format in [MessageFormat::Json, MessageFormat::MessagePack] {
+            let codec = codec(format);
+            let bytes = codec.encode(json).unwrap();
+            let (text, decoded) = codec.decode(&bytes);
+            assert_eq!(decoded, format);
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&text).unwrap(),
+                serde_json::from_str::<serde_json::Value>(json).unwrap()
+            );
+        }
+        let bytes = codec(MessageFormat::Hex).encode("00ff10").unwrap();
+        assert_eq!(bytes, vec![0x00, 0xff, 0x10]);
+        assert_eq!(codec(MessageFormat::Base64).decode(&bytes).0, "AP8Q");
+    }
+
+    /// 把 schema 写入临时文件，返回文件路径
+    fn schema_file(extension: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "easy-kafka-{}.{}",
+            uuid::Uuid::new_v4().simple(),
+            extension
+        ));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn assert_json_eq(text: &str, expected: &str) {
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(text).unwrap(),
+            serde_json::from_str::<serde_json::Value>(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn avro_round_trip_test() {
+        let path = schema_file(
+            "avsc",
+            r#"{"type":"record","name":"Order","fields":[
+                {"name":"id","type":"long"},
+                {"name":"status","type":"string"},
+                {"name":"tags","type":{"type":"array","items":"string"}}
+            ]}"#,
+        );
+        let codec = FieldCodec::build(&FormatConfig {
+            format: MessageFormat::Avro,
+            schema_path: Some(path.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+        let json = r#"{"id":12345,"status":"PAID","tags":["vip"]}"#;
+        let bytes = codec.encode(json).unwrap();
+        let (text, decoded) = codec.decode(&bytes);
+        assert_eq!(decoded, MessageFormat::Avro);
+        assert_json_eq(&text, json);
+        assert!(codec.encode(r#"{"id":"x"}"#).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn protobuf_round_trip_test() {
+        let path = schema_file(
+            "proto",
+            r#"syntax = "proto3";
+            package shop;
+            message Order {
+                int64 id = 1;
+                string status = 2;
+                repeated string tags = 3;
+            }"#,
+        );
+        let codec = FieldCodec::build(&FormatConfig {
+            format: MessageFormat::Protobuf,
+            schema_path: Some(path.clone()),
+            message_type: Some("shop.Order".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        // proto3 的 JSON 映射中 int64 输出为字符串
+        let bytes = codec
+            .encode(r#"{"id":12345,"status":"PAID","tags":["vip"]}"#)
+            .unwrap();
+        let (text, decoded) = codec.decode(&bytes);
+        assert_eq!(decoded, MessageFormat::Protobuf);
+        assert_json_eq(&text, r#"{"id":"12345","status":"PAID","tags":["vip"]}"#);
+        assert!(codec.encode(r#"{"missing":1}"#).is_err());
+        assert!(FieldCodec::build(&FormatConfig {
+            format: MessageFormat::Protobuf,
+            schema_path: Some(path.clone()),
+            message_type: Some("shop.Missing".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn fallback_test() {
+        let bytes = [0xc3, 0x28];
+        assert_eq!(
+            codec(MessageFormat::Text).decode(&bytes),
+            ("c328".to_string(), MessageFormat::Hex)
+        );
+        let text = TextCodec.serialize("{not json").unwrap();
+        assert_eq!(
+            codec(MessageFormat::Json).decode(&text).1,
+            MessageFormat::Hex
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! 消费者组协议相关的计算：成员分配信息的解析和 coordinator 分区的定位
+
+/// 解析 consumer 协议的成员分配信息（ConsumerProtocolAssignment），返回 topic 和分区列表
+pub fn decode_assignment(bytes: &[u8]) -> Result<Vec<(String, Vec<i32>)>, String> {
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut reader = Reader { bytes, position: 0 };
+    let _version = reader.read_i16()?;
+    let topic_count = reader.read_i32()?;
+    let mut assignments = Vec::new();
+    for _ in 0..topic_count.max(0) {
+        let topic = reader.read_string()?;
+        let partition_count = reader.read_i32()?;
+        let mut partitions = Vec::new();
+        for _ in 0..partition_count.max(0) {
+            partitions.push(reader.read_i32()?);
+        }
+        assignments.push((topic, partitions));
+    }
+    Ok(assignments)
+}
+
+/// 消费者组的 coordinator 是 __consumer_offsets 中该分区的 leader，算法与 broker 一致，
+/// 分区数不大于 0 时返回 None
+pub fn coordinator_partition(group_id: &str, partition_count: i32) -> Option<i32> {
+    if partition_count <= 0 {
+        return None;
+    }
+    let hash = group_id.encode_utf16().fold(0i32, |hash, unit| {
+        hash.wrapping_mul(31).wrapping_add(unit as i32)
+    });
+    let hash = if hash == i32::MIN { 0 } else { hash.abs() };
+    Some(hash % partition_count)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], String> {
+        let end = self.position + length;
+        let slice = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| "分配信息格式错误".to_string())?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_i16(&mut self) -> Result<i16, String> {
+        let bytes = self.take(2)?;
+        Ok(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, String> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        let length = self.read_i16()?;
+        let bytes = self.take(length.max(0) as usize)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod group_protocol_test {
+    use crate::utils::group_protocol::{coordinator_partition, decode_assignment};
+
+    #[test]
+    fn decode_assignment_test() {
+        let mut bytes = vec![0, 1, 0, 0, 0, 2];
+        for (topic, partitions) in [("orders", vec![0i32, 2]), ("payments", vec![1])] {
+            bytes.extend_from_slice(&(topic.len() as i16).to_be_bytes());
+            bytes.extend_from_slice(topic.as_bytes());
+            bytes.extend_from_slice(&(partitions.len() as i32).to_be_bytes());
+            for partition in partitions {
+                bytes.extend_from_slice(&partition.to_be_bytes());
+            }
+        }
+        // user data
+        bytes.extend_from_slice(&(-1i32).to_be_bytes());
+
+        assert_eq!(
+            decode_assignment(&bytes).unwrap(),
+            vec![
+                ("orders".to_string(), vec![0, 2]),
+                ("payments".to_string(), vec![1])
+            ]
+        );
+        assert!(decode_assignment(&[]).unwrap().is_empty());
+        assert!(decode_assignment(&bytes[..9]).is_err());
+    }
+
+    #[test]
+    fn coordinator_partition_test() {
+        assert_eq!(coordinator_partition("hello", 50), Some(22));
+        assert_eq!(coordinator_partition("test-group-1", 50), Some(36));
+        assert_eq!(coordinator_partition("easy-kafka", 50), Some(27));
+        assert_eq!(coordinator_partition("消费组", 50), Some(35));
+        assert_eq!(coordinator_partition("hello", 0), None);
+    }
+}
@@ -0,0 +1,607 @@
+//! 消息过滤表达式，在后端过滤消息，避免把所有消息传给前端。
+//!
+//! 表达式由比较条件和 and / or / not 组合而成，例如：
+//!
+//! ```text
+//! key ~ "^order-" and $.order.id == 12345 and header.source == "replay"
+//! timestamp >= 1700000000000 and (offset < 100 or not value contains "debug")
+//! ```
+//!
+//! 支持的字段：key、value、topic、partition、offset、timestamp、header.<名称>，
+//! 以及 `$` 开头的 JSON 路径（如 `$.items[0].sku`），JSON 路径会把 value 解析为 JSON 后取值。
+//! 支持的运算符：`==`、`!=`、`>`、`>=`、`<`、`<=`、`~`（正则匹配）、`contains`。
+
+use crate::entity::response::consumer::ConsumerRecord;
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// 编译后的过滤表达式，同一个会话中只编译一次
+#[derive(Debug)]
+pub struct MessageFilter {
+    expr: Expr,
+    uses_json: bool,
+}
+
+impl MessageFilter {
+    pub fn compile(expression: &str) -> Result<Self, String> {
+        let tokens = tokenize(expression)?;
+        if tokens.is_empty() {
+            return Err("过滤表达式不能为空".to_string());
+        }
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("无法识别的内容: {:?}", token));
+        }
+        let uses_json = expr.uses_json();
+        Ok(Self { expr, uses_json })
+    }
+
+    pub fn matches(&self, record: &ConsumerRecord) -> bool {
+        let json = if self.uses_json {
+            record
+                .value
+                .as_deref()
+                .and_then(|value| serde_json::from_str::<Value>(value).ok())
+        } else {
+            None
+        };
+        self.expr.evaluate(record, json.as_ref())
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        literal: Value,
+    },
+    Match {
+        field: Field,
+        regex: Regex,
+    },
+}
+
+#[derive(Debug)]
+enum Field {
+    Topic,
+    Key,
+    Value,
+    Partition,
+    Offset,
+    Timestamp,
+    Header(String),
+    JsonPath(Vec<PathSegment>),
+}
+
+#[derive(Debug)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+impl Expr {
+    fn uses_json(&self) -> bool {
+        match self {
+            Expr::And(left, right) | Expr::Or(left, right) => left.uses_json() || right.uses_json(),
+            Expr::Not(expr) => expr.uses_json(),
+            Expr::Compare { field, .. } | Expr::Match { field, .. } => {
+                matches!(field, Field::JsonPath(_))
+            }
+        }
+    }
+
+    fn evaluate(&self, record: &ConsumerRecord, json: Option<&Value>) -> bool {
+        match self {
+            Expr::And(left, right) => left.evaluate(record, json) && right.evaluate(record, json),
+            Expr::Or(left, right) => left.evaluate(record, json) || right.evaluate(record, json),
+            Expr::Not(expr) => !expr.evaluate(record, json),
+            Expr::Match { field, regex } => match field.resolve(record, json) {
+                Some(Value::String(text)) => regex.is_match(&text),
+                Some(Value::Null) | None => false,
+                Some(other) => regex.is_match(&other.to_string()),
+            },
+            Expr::Compare { field, op, literal } => {
+                compare(field.resolve(record, json).as_ref(), *op, literal)
+            }
+        }
+    }
+}
+
+impl Field {
+    fn resolve(&self, record: &ConsumerRecord, json: Option<&Value>) -> Option<Value> {
+        match self {
+            Field::Topic => Some(Value::from(record.topic.as_str())),
+            Field::Key => record.key.as_deref().map(Value::from),
+            Field::Value => record.value.as_deref().map(Value::from),
+            Field::Partition => Some(Value::from(record.partition)),
+            Field::Offset => Some(Value::from(record.offset)),
+            Field::Timestamp => record.timestamp.map(Value::from),
+            Field::Header(name) => record
+                .headers
+                .iter()
+                .find(|header| &header.key == name)
+                .map(|header| {
+                    header
+                        .value
+                        .as_deref()
+                        .map(Value::from)
+                        .unwrap_or(Value::Null)
+                }),
+            Field::JsonPath(path) => {
+                let mut current = json?;
+                for segment in path {
+                    current = match segment {
+                        PathSegment::Key(key) => current.get(key)?,
+                        PathSegment::Index(index) => current.get(index)?,
+                    };
+                }
+                Some(current.clone())
+            }
+        }
+    }
+}
+
+fn compare(actual: Option<&Value>, op: CompareOp, literal: &Value) -> bool {
+    let actual = match actual {
+        Some(actual) => actual,
+        // 字段不存在时按 null 处理
+        None => &Value::Null,
+    };
+    match op {
+        CompareOp::Eq => values_equal(actual, literal),
+        CompareOp::Ne => !values_equal(actual, literal),
+        CompareOp::Contains => match actual {
+            Value::String(text) => match literal {
+                Value::String(literal) => text.contains(literal.as_str()),
+                other => text.contains(&other.to_string()),
+            },
+            Value::Array(items) => items.iter().any(|item| values_equal(item, literal)),
+            _ => false,
+        },
+        CompareOp::Gt | CompareOp::Ge | CompareOp::Lt | CompareOp::Le => {
+            let ordering = match (as_number(actual), as_number(literal)) {
+                (Some(actual), Some(literal)) => actual.compare(&literal),
+                _ => match (actual, literal) {
+                    (Value::String(actual), Value::String(literal)) => Some(actual.cmp(literal)),
+                    _ => None,
+                },
+            };
+            match ordering {
+                Some(ordering) => match op {
+                    CompareOp::Gt => ordering == Ordering::Greater,
+                    CompareOp::Ge => ordering != Ordering::Less,
+                    CompareOp::Lt => ordering == Ordering::Less,
+                    _ => ordering != Ordering::Greater,
+                },
+                None => false,
+            }
+        }
+    }
+}
+
+/// 任意一边为数字时按数值比较，其余按 JSON 值比较
+fn values_equal(actual: &Value, literal: &Value) -> bool {
+    if actual.is_number() || literal.is_number() {
+        if let (Some(actual), Some(literal)) = (as_number(actual), as_number(literal)) {
+            return actual.compare(&literal) == Some(Ordering::Equal);
+        }
+    }
+    match (actual, literal) {
+        (Value::String(actual), Value::Bool(literal)) => actual == &literal.to_string(),
+        _ => actual == literal,
+    }
+}
+
+/// 比较用的数值，两边都是整数时精确比较，否则转换为 f64 比较
+#[derive(Debug, Clone, Copy)]
+enum Numeric {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Numeric {
+    fn compare(&self, other: &Numeric) -> Option<Ordering> {
+        match (self, other) {
+            (Numeric::Integer(a), Numeric::Integer(b)) => Some(a.cmp(b)),
+            _ => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            Numeric::Integer(value) => *value as f64,
+            Numeric::Float(value) => *value,
+        }
+    }
+}
+
+fn as_number(value: &Value) -> Option<Numeric> {
+    match value {
+        Value::Number(number) => Some(to_numeric(number)),
+        Value::String(text) => parse_number(text.trim()).map(|number| to_numeric(&number)),
+        _ => None,
+    }
+}
+
+fn to_numeric(number: &serde_json::Number) -> Numeric {
+    match (number.as_i64(), number.as_u64()) {
+        (Some(value), _) => Numeric::Integer(value as i128),
+        (_, Some(value)) => Numeric::Integer(value as i128),
+        _ => Numeric::Float(number.as_f64().unwrap_or(f64::NAN)),
+    }
+}
+
+/// 解析数字，整数保留精确值，超出 i64/u64 范围或带小数点时按 f64 解析
+fn parse_number(text: &str) -> Option<serde_json::Number> {
+    if !text.contains(['.', 'e', 'E']) {
+        if let Ok(value) = text.parse::<i64>() {
+            return Some(value.into());
+        }
+        if let Ok(value) = text.parse::<u64>() {
+            return Some(value.into());
+        }
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Path(String),
+    Str(String),
+    Number(serde_json::Number),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if c.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                index += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                index += 1;
+            }
+            '"' | '\'' => {
+                let mut text = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        // 只转义引号和反斜杠，其余反斜杠原样保留，便于书写正则
+                        Some('\\') => {
+                            match chars.get(index + 1) {
+                                Some(next) if *next == c || *next == '\\' => text.push(*next),
+                                Some(next) => {
+                                    text.push('\\');
+                                    text.push(*next);
+                                }
+                                None => return Err("字符串未结束".to_string()),
+                            }
+                            index += 2;
+                        }
+                        Some(quote) if *quote == c => {
+                            index += 1;
+                            break;
+                        }
+                        Some(other) => {
+                            text.push(*other);
+                            index += 1;
+                        }
+                        None => return Err("字符串未结束".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            '$' => {
+                let start = index;
+                while index < chars.len()
+                    && (chars[index].is_alphanumeric() || "$._-[]".contains(chars[index]))
+                {
+                    index += 1;
+                }
+                tokens.push(Token::Path(chars[start..index].iter().collect()));
+            }
+            '=' | '!' | '>' | '<' | '~' | '&' | '|' => {
+                let next = chars.get(index + 1).copied();
+                let (op, width) = match (c, next) {
+                    ('=', Some('=')) => ("==", 2),
+                    ('!', Some('=')) => ("!=", 2),
+                    ('>', Some('=')) => (">=", 2),
+                    ('<', Some('=')) => ("<=", 2),
+                    ('&', Some('&')) => ("and", 2),
+                    ('|', Some('|')) => ("or", 2),
+                    ('=', _) => ("==", 1),
+                    ('!', _) => ("not", 1),
+                    ('>', _) => (">", 1),
+                    ('<', _) => ("<", 1),
+                    ('~', _) => ("~", 1),
+                    _ => return Err(format!("无法识别的运算符: {}", c)),
+                };
+                tokens.push(Token::Op(op));
+                index += width;
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = index;
+                index += 1;
+                while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.')
+                {
+                    index += 1;
+                }
+                let text = chars[start..index].iter().collect::<String>();
+                let number =
+                    parse_number(&text).ok_or_else(|| format!("无法识别的数字: {}", text))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = index;
+                while index < chars.len()
+                    && (chars[index].is_alphanumeric() || "_.-".contains(chars[index]))
+                {
+                    index += 1;
+                }
+                let word = chars[start..index].iter().collect::<String>();
+                match word.to_lowercase().as_str() {
+                    "and" => tokens.push(Token::Op("and")),
+                    "or" => tokens.push(Token::Op("or")),
+                    "not" => tokens.push(Token::Op("not")),
+                    "contains" => tokens.push(Token::Op("contains")),
+                    _ => tokens.push(Token::Ident(word)),
+                }
+            }
+            other => return Err(format!("无法识别的字符: {}", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(current)) if *current == op) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.eat_op("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_unary()?;
+        while self.eat_op("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.eat_op("not") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.position += 1;
+            let expr = self.parse_or()?;
+            if self.next() != Some(Token::RParen) {
+                return Err("缺少右括号".to_string());
+            }
+            return Ok(expr);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let field = match self.next() {
+            Some(Token::Ident(name)) => parse_field(&name)?,
+            Some(Token::Path(path)) => Field::JsonPath(parse_path(&path)?),
+            other => return Err(format!("需要字段名，实际为: {:?}", other)),
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            other => return Err(format!("需要运算符，实际为: {:?}", other)),
+        };
+        let literal = match self.next() {
+            Some(Token::Str(text)) => Value::String(text),
+            Some(Token::Number(number)) => Value::Number(number),
+            Some(Token::Ident(word)) => match word.to_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => return Err(format!("字符串需要使用引号: {}", word)),
+            },
+            other => return Err(format!("需要比较值，实际为: {:?}", other)),
+        };
+
+        let op = match op {
+            "~" => {
+                let pattern = match literal {
+                    Value::String(pattern) => pattern,
+                    other => other.to_string(),
+                };
+                let regex = Regex::new(&pattern).map_err(|e| format!("正则表达式错误: {}", e))?;
+                return Ok(Expr::Match { field, regex });
+            }
+            "==" => CompareOp::Eq,
+            "!=" => CompareOp::Ne,
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            "contains" => CompareOp::Contains,
+            other => return Err(format!("运算符位置错误: {}", other)),
+        };
+        Ok(Expr::Compare { field, op, literal })
+    }
+}
+
+fn parse_field(name: &str) -> Result<Field, String> {
+    if let Some(header) = name.strip_prefix("header.") {
+        return Ok(Field::Header(header.to_string()));
+    }
+    match name.to_lowercase().as_str() {
+        "topic" => Ok(Field::Topic),
+        "key" => Ok(Field::Key),
+        "value" => Ok(Field::Value),
+        "partition" => Ok(Field::Partition),
+        "offset" => Ok(Field::Offset),
+        "timestamp" => Ok(Field::Timestamp),
+        _ => Err(format!("无法识别的字段: {}", name)),
+    }
+}
+
+/// 解析 `$.a.b[0]` 形式的 JSON 路径
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| format!("JSON路径需要以$开头: {}", path))?;
+    while !rest.is_empty() {
+        if let Some(next) = rest.strip_prefix('.') {
+            let end = next.find(['.', '[']).unwrap_or(next.len());
+            if end == 0 {
+                return Err(format!("JSON路径格式错误: {}", path));
+            }
+            segments.push(PathSegment::Key(next[..end].to_string()));
+            rest = &next[end..];
+        } else if let Some(next) = rest.strip_prefix('[') {
+            let end = next
+                .find(']')
+                .ok_or_else(|| format!("JSON路径格式错误: {}", path))?;
+            let index = next[..end]
+                .parse()
+                .map_err(|_| format!("JSON路径下标错误: {}", path))?;
+            segments.push(PathSegment::Index(index));
+            rest = &next[end + 1..];
+        } else {
+            return Err(format!("JSON路径格式错误: {}", path));
+        }
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod message_filter_test {
+    use crate::entity::request::topic::MessageFormat;
+    use crate::entity::response::consumer::{ConsumerRecord, RecordHeader};
+    use crate::utils::message_filter::MessageFilter;
+
+    fn record() -> ConsumerRecord {
+        ConsumerRecord {
+            topic: "orders".to_string(),
+            partition: 2,
+            offset: 120,
+            key: Some("order-12345".to_string()),
+            value: Some(
+                r#"{"order":{"id":12345,"status":"PAID"},"items":[{"sku":"A-1"}],"tags":["vip"]}"#
+                    .to_string(),
+            ),
+            key_format: Some(MessageFormat::Text),
+            value_format: Some(MessageFormat::Json),
+            headers: vec![RecordHeader {
+                key: "source".to_string(),
+                value: Some("replay".to_string()),
+            }],
+            timestamp: Some(1700000000000),
+            timestamp_type: "CreateTime".to_string(),
+        }
+    }
+
+    fn matches(expression: &str) -> bool {
+        MessageFilter::compile(expression)
+            .unwrap()
+            .matches(&record())
+    }
+
+    #[test]
+    fn filter_test() {
+        assert!(matches(r#"key ~ "^order-\d+$""#));
+        assert!(matches("$.order.id == 12345 and $.order.status == 'PAID'"));
+        assert!(matches(
+            "$.items[0].sku == \"A-1\" and $.tags contains 'vip'"
+        ));
+        assert!(matches("header.source == 'replay' && partition == 2"));
+        assert!(matches("timestamp >= 1700000000000 and offset < 200"));
+        assert!(matches(
+            "not (offset > 200 or key == 'x') and value contains \"PAID\""
+        ));
+        assert!(!matches("$.order.missing == 1"));
+        assert!(matches("$.order.missing == null"));
+        assert!(!matches("header.trace == 'abc'"));
+    }
+
+    #[test]
+    fn large_integer_test() {
+        let mut record = record();
+        record.value = Some(r#"{"id":9007199254740992,"amount":1.5}"#.to_string());
+        let matches =
+            |expression: &str| MessageFilter::compile(expression).unwrap().matches(&record);
+        assert!(!matches("$.id == 9007199254740993"));
+        assert!(matches("$.id == 9007199254740992"));
+        assert!(matches("$.id < 9007199254740993"));
+        assert!(matches("$.id != 18446744073709551615"));
+        assert!(matches("$.amount > 1 and $.amount == 1.5"));
+    }
+
+    #[test]
+    fn compile_error_test() {
+        assert!(MessageFilter::compile("").is_err());
+        assert!(MessageFilter::compile("unknown == 1").is_err());
+        assert!(MessageFilter::compile("key ~ '('").is_err());
+        assert!(MessageFilter::compile("(key == 'a'").is_err());
+        assert!(MessageFilter::compile("key == 'a' offset").is_err());
+    }
+}
@@ -1 +1,6 @@
-
+pub mod codec;
+pub mod group_protocol;
+pub mod message_filter;
+pub mod partitioner;
+pub mod record_file;
+pub mod security;
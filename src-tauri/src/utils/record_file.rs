@@ -0,0 +1,207 @@
+use crate::entity::request::producer::{
+    FieldMapping, MessageHeader, ProduceMessageRequest, RecordFileFormat,
+};
+use serde_json::{Map, Value};
+
+/// 文件中的一行记录，CSV 的每一行会按表头转换为 JSON 对象
+pub struct FileRecord {
+    pub line: u64,
+    pub record: Result<Value, String>,
+}
+
+/// 解析 JSONL 或 CSV 文件内容，limit 为空时解析全部行
+pub fn parse_records(
+    content: &str,
+    format: RecordFileFormat,
+    limit: Option<usize>,
+) -> Result<Vec<FileRecord>, String> {
+    let limit = limit.unwrap_or(usize::MAX);
+    match format {
+        RecordFileFormat::Jsonl => Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .take(limit)
+            .map(|(index, line)| FileRecord {
+                line: index as u64 + 1,
+                record: serde_json::from_str(line).map_err(|e| e.to_string()),
+            })
+            .collect()),
+        RecordFileFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(true)
+                .flexible(false)
+                .from_reader(content.as_bytes());
+            let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+            Ok(reader
+                .records()
+                .take(limit)
+                .enumerate()
+                .map(|(index, row)| match row {
+                    Ok(row) => FileRecord {
+                        line: row
+                            .position()
+                            .map(|item| item.line())
+                            .unwrap_or(index as u64 + 2),
+                        record: Ok(Value::Object(
+                            headers
+                                .iter()
+                                .zip(row.iter())
+                                .map(|(name, value)| (name.to_string(), Value::from(value)))
+                                .collect::<Map<_, _>>(),
+                        )),
+                    },
+                    Err(e) => FileRecord {
+                        line: e
+                            .position()
+                            .map(|item| item.line())
+                            .unwrap_or(index as u64 + 2),
+                        record: Err(e.to_string()),
+                    },
+                })
+                .collect())
+        }
+    }
+}
+
+/// 按字段映射将一行记录转换为待发送的消息
+pub fn map_record(
+    topic: &str,
+    record: &Value,
+    mapping: &FieldMapping,
+) -> Result<ProduceMessageRequest, String> {
+    let object = record.as_object().ok_or("记录不是JSON对象")?;
+    let key = match mapping.key.as_deref() {
+        Some(field) => field_text(object, field)?,
+        None => None,
+    };
+    let value = match mapping.value.as_deref() {
+        Some(field) => field_text(object, field)?,
+        None => Some(record.to_string()),
+    };
+    let partition = match mapping.partition.as_deref() {
+        Some(field) => field_number(object, field)?
+            .map(|item| i32::try_from(item).map_err(|_| format!("字段[{}]超出分区范围", field)))
+            .transpose()?,
+        None => None,
+    };
+    let timestamp = match mapping.timestamp.as_deref() {
+        Some(field) => field_number(object, field)?,
+        None => None,
+    };
+
+    let mut headers = Vec::new();
+    for field in mapping.headers.iter() {
+        match object.get(field) {
+            Some(Value::Object(entries)) => {
+                for (key, value) in entries {
+                    headers.push(MessageHeader {
+                        key: key.clone(),
+                        value: value_text(value),
+                    });
+                }
+            }
+            Some(value) => headers.push(MessageHeader {
+                key: field.clone(),
+                value: value_text(value),
+            }),
+            None => return Err(format!("字段[{}]不存在", field)),
+        }
+    }
+
+    Ok(ProduceMessageRequest {
+        topic: topic.to_string(),
+        key,
+        value,
+        headers,
+        partition,
+        timestamp,
+    })
+}
+
+fn field_text(object: &Map<String, Value>, field: &str) -> Result<Option<String>, String> {
+    object
+        .get(field)
+        .map(value_text)
+        .ok_or_else(|| format!("字段[{}]不存在", field))
+}
+
+fn field_number(object: &Map<String, Value>, field: &str) -> Result<Option<i64>, String> {
+    match object.get(field) {
+        None => Err(format!("字段[{}]不存在", field)),
+        Some(Value::Null) => Ok(None),
+        Some(Value::Number(number)) => number
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| format!("字段[{}]不是整数", field)),
+        Some(Value::String(text)) if text.trim().is_empty() => Ok(None),
+        Some(Value::String(text)) => text
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("字段[{}]不是整数", field)),
+        Some(_) => Err(format!("字段[{}]不是整数", field)),
+    }
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod record_file_test {
+    use crate::entity::request::producer::{FieldMapping, RecordFileFormat};
+    use crate::utils::record_file::{map_record, parse_records};
+
+    #[test]
+    fn csv_mapping_test() {
+        let content = "id,name,partition,ts,trace\n1,alice,0,1700000000000,t-1\n2,bob,x,,t-2\n";
+        let mapping = FieldMapping {
+            key: Some("id".to_string()),
+            value: Some("name".to_string()),
+            headers: vec!["trace".to_string()],
+            partition: Some("partition".to_string()),
+            timestamp: Some("ts".to_string()),
+        };
+        let records = parse_records(content, RecordFileFormat::Csv, None).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let record = records[0].record.as_ref().unwrap();
+        let message = map_record("test", record, &mapping).unwrap();
+        assert_eq!(message.key.as_deref(), Some("1"));
+        assert_eq!(message.value.as_deref(), Some("alice"));
+        assert_eq!(message.partition, Some(0));
+        assert_eq!(message.timestamp, Some(1700000000000));
+        assert_eq!(message.headers[0].key, "trace");
+
+        let record = records[1].record.as_ref().unwrap();
+        assert_eq!(records[1].line, 3);
+        assert!(map_record("test", record, &mapping).is_err());
+    }
+
+    #[test]
+    fn jsonl_mapping_test() {
+        let content = "{\"id\":1,\"headers\":{\"source\":\"replay\"}}\n\nnot json\n";
+        let mapping = FieldMapping {
+            key: Some("id".to_string()),
+            headers: vec!["headers".to_string()],
+            ..Default::default()
+        };
+        let records = parse_records(content, RecordFileFormat::Jsonl, Some(10)).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let message = map_record("test", records[0].record.as_ref().unwrap(), &mapping).unwrap();
+        assert_eq!(message.key.as_deref(), Some("1"));
+        let value: serde_json::Value = serde_json::from_str(&message.value.unwrap()).unwrap();
+        assert_eq!(value["headers"]["source"], "replay");
+        assert_eq!(message.headers[0].key, "source");
+        assert_eq!(message.headers[0].value.as_deref(), Some("replay"));
+
+        assert_eq!(records[1].line, 3);
+        assert!(records[1].record.is_err());
+    }
+}
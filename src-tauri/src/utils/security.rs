@@ -0,0 +1,434 @@
+//! 集群连接的安全配置：通信协议、SASL 机制、Kerberos、OAuth 和 SSL 证书，转换为 librdkafka 的配置项
+
+use crate::entity::db_entity::cluster;
+use std::path::Path;
+use std::str::FromStr;
+
+/// 未配置服务名时 broker 使用的 Kerberos 服务名
+const DEFAULT_KERBEROS_SERVICE: &str = "kafka";
+
+/// 客户端和 broker 之间的通信协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityProtocol {
+    Plaintext,
+    Ssl,
+    SaslPlaintext,
+    SaslSsl,
+}
+
+impl SecurityProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityProtocol::Plaintext => "PLAINTEXT",
+            SecurityProtocol::Ssl => "SSL",
+            SecurityProtocol::SaslPlaintext => "SASL_PLAINTEXT",
+            SecurityProtocol::SaslSsl => "SASL_SSL",
+        }
+    }
+
+    pub fn is_ssl(&self) -> bool {
+        matches!(self, SecurityProtocol::Ssl | SecurityProtocol::SaslSsl)
+    }
+
+    pub fn is_sasl(&self) -> bool {
+        matches!(
+            self,
+            SecurityProtocol::SaslPlaintext | SecurityProtocol::SaslSsl
+        )
+    }
+}
+
+impl FromStr for SecurityProtocol {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "PLAINTEXT" => Ok(SecurityProtocol::Plaintext),
+            "SSL" => Ok(SecurityProtocol::Ssl),
+            "SASL_PLAINTEXT" => Ok(SecurityProtocol::SaslPlaintext),
+            "SASL_SSL" => Ok(SecurityProtocol::SaslSsl),
+            _ => Err(format!("不支持的安全协议: {}", value)),
+        }
+    }
+}
+
+/// SASL 认证机制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaslMechanism {
+    Plain,
+    ScramSha256,
+    ScramSha512,
+    Gssapi,
+    OAuthBearer,
+}
+
+impl SaslMechanism {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SaslMechanism::Plain => "PLAIN",
+            SaslMechanism::ScramSha256 => "SCRAM-SHA-256",
+            SaslMechanism::ScramSha512 => "SCRAM-SHA-512",
+            SaslMechanism::Gssapi => "GSSAPI",
+            SaslMechanism::OAuthBearer => "OAUTHBEARER",
+        }
+    }
+}
+
+impl FromStr for SaslMechanism {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "PLAIN" => Ok(SaslMechanism::Plain),
+            "SCRAM-SHA-256" => Ok(SaslMechanism::ScramSha256),
+            "SCRAM-SHA-512" => Ok(SaslMechanism::ScramSha512),
+            "GSSAPI" => Ok(SaslMechanism::Gssapi),
+            "OAUTHBEARER" => Ok(SaslMechanism::OAuthBearer),
+            _ => Err(format!("不支持的SASL机制: {}", value)),
+        }
+    }
+}
+
+/// 集群的通信协议，早期版本没有保存协议，根据 ssl 开关和是否配置了 SASL 机制推断
+pub fn security_protocol(connect: &cluster::Model) -> Result<SecurityProtocol, String> {
+    if let Some(protocol) = non_empty(&connect.security_protocol) {
+        return protocol.parse();
+    }
+    Ok(match (connect.ssl, non_empty(&connect.sasl).is_some()) {
+        (false, false) => SecurityProtocol::Plaintext,
+        (true, false) => SecurityProtocol::Ssl,
+        (false, true) => SecurityProtocol::SaslPlaintext,
+        (true, true) => SecurityProtocol::SaslSsl,
+    })
+}
+
+/// 集群的 SASL 机制，未配置时为 PLAIN
+pub fn sasl_mechanism(connect: &cluster::Model) -> Result<SaslMechanism, String> {
+    match non_empty(&connect.sasl) {
+        Some(mechanism) => mechanism.parse(),
+        None => Ok(SaslMechanism::Plain),
+    }
+}
+
+/// 生成 librdkafka 的安全配置项，所有类型的客户端使用相同的配置
+pub fn security_properties(
+    connect: &cluster::Model,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let protocol = security_protocol(connect)?;
+    let mut properties = vec![("security.protocol", protocol.as_str().to_string())];
+    if protocol.is_sasl() {
+        let mechanism = sasl_mechanism(connect)?;
+        properties.push(("sasl.mechanisms", mechanism.as_str().to_string()));
+        match mechanism {
+            SaslMechanism::Plain | SaslMechanism::ScramSha256 | SaslMechanism::ScramSha512 => {
+                let username = non_empty(&connect.username)
+                    .ok_or_else(|| format!("{}认证需要配置用户名", mechanism.as_str()))?;
+                properties.push(("sasl.username", username));
+                properties.push((
+                    "sasl.password",
+                    connect.password.clone().unwrap_or_default(),
+                ));
+            }
+            SaslMechanism::Gssapi => {
+                properties.push((
+                    "sasl.kerberos.service.name",
+                    non_empty(&connect.kerberos_service_name)
+                        .unwrap_or_else(|| DEFAULT_KERBEROS_SERVICE.to_string()),
+                ));
+                // 配置了 keytab 时由 librdkafka 定期执行 kinit 刷新票据，否则使用系统的票据缓存
+                for (key, value) in [
+                    ("sasl.kerberos.principal", &connect.kerberos_principal),
+                    ("sasl.kerberos.keytab", &connect.kerberos_keytab),
+                ] {
+                    if let Some(value) = non_empty(value) {
+                        properties.push((key, value));
+                    }
+                }
+            }
+            SaslMechanism::OAuthBearer => {
+                // 使用 librdkafka 内置的 OIDC client credentials 流程，token 过期前会自动刷新
+                properties.push(("sasl.oauthbearer.method", "oidc".to_string()));
+                for (key, name, value) in [
+                    (
+                        "sasl.oauthbearer.token.endpoint.url",
+                        "token地址",
+                        &connect.oauth_token_endpoint,
+                    ),
+                    (
+                        "sasl.oauthbearer.client.id",
+                        "client id",
+                        &connect.oauth_client_id,
+                    ),
+                    (
+                        "sasl.oauthbearer.client.secret",
+                        "client secret",
+                        &connect.oauth_client_secret,
+                    ),
+                ] {
+                    let value = non_empty(value)
+                        .ok_or_else(|| format!("OAUTHBEARER认证需要配置{}", name))?;
+                    properties.push((key, value));
+                }
+                if let Some(scope) = non_empty(&connect.oauth_scope) {
+                    properties.push(("sasl.oauthbearer.scope", scope));
+                }
+            }
+        }
+    }
+    if protocol.is_ssl() {
+        for (key, value) in [
+            ("ssl.ca.location", &connect.ssl_ca_location),
+            (
+                "ssl.certificate.location",
+                &connect.ssl_certificate_location,
+            ),
+            ("ssl.key.location", &connect.ssl_key_location),
+            ("ssl.key.password", &connect.ssl_key_password),
+        ] {
+            if let Some(value) = non_empty(value) {
+                properties.push((key, value));
+            }
+        }
+        if !connect.ssl_verify_hostname {
+            properties.push(("ssl.endpoint.identification.algorithm", "none".to_string()));
+        }
+    }
+    Ok(properties)
+}
+
+/// 保存集群前检查安全配置，使用 SSL 时证书和私钥文件需要存在
+pub fn validate_security(connect: &cluster::Model) -> Result<(), String> {
+    security_properties(connect)?;
+    let protocol = security_protocol(connect)?;
+    if protocol.is_sasl() && sasl_mechanism(connect)? == SaslMechanism::Gssapi {
+        if let Some(keytab) = non_empty(&connect.kerberos_keytab) {
+            if !Path::new(&keytab).is_file() {
+                return Err(format!("keytab文件不存在: {}", keytab));
+            }
+        }
+    }
+    if !protocol.is_ssl() {
+        return Ok(());
+    }
+    if non_empty(&connect.ssl_certificate_location).is_some()
+        != non_empty(&connect.ssl_key_location).is_some()
+    {
+        return Err("客户端证书和私钥需要同时配置".to_string());
+    }
+    for (name, value) in [
+        ("CA证书", &connect.ssl_ca_location),
+        ("客户端证书", &connect.ssl_certificate_location),
+        ("客户端私钥", &connect.ssl_key_location),
+    ] {
+        if let Some(path) = non_empty(value) {
+            if !Path::new(&path).is_file() {
+                return Err(format!("{}文件不存在: {}", name, path));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 认证或 SSL 握手失败时，根据集群的安全配置给出排查提示
+pub fn describe_auth_failure(connect: &cluster::Model, reason: &str) -> String {
+    let protocol = match security_protocol(connect) {
+        Ok(protocol) => protocol,
+        Err(msg) => return msg,
+    };
+    if !protocol.is_sasl() || reason.contains("SSL") {
+        return format!(
+            "SSL握手失败: {}，请检查CA证书、客户端证书和私钥，自签名证书可关闭主机名校验",
+            reason
+        );
+    }
+    let unset = || "未配置".to_string();
+    match sasl_mechanism(connect) {
+        Ok(SaslMechanism::Gssapi) => format!(
+            "Kerberos认证失败(principal: {}, keytab: {}, 服务名: {}): {}，\
+             请检查keytab与principal是否匹配、KDC是否可访问以及krb5.conf配置",
+            non_empty(&connect.kerberos_principal).unwrap_or_else(unset),
+            non_empty(&connect.kerberos_keytab).unwrap_or_else(unset),
+            non_empty(&connect.kerberos_service_name)
+                .unwrap_or_else(|| DEFAULT_KERBEROS_SERVICE.to_string()),
+            reason
+        ),
+        Ok(SaslMechanism::OAuthBearer) => format!(
+            "OAuth认证失败(token地址: {}, client id: {}): {}，\
+             请检查token地址是否可访问，以及client id、client secret和scope是否正确",
+            non_empty(&connect.oauth_token_endpoint).unwrap_or_else(unset),
+            non_empty(&connect.oauth_client_id).unwrap_or_else(unset),
+            reason
+        ),
+        Ok(mechanism) => format!(
+            "{}认证失败(用户名: {}): {}，请检查用户名、密码和broker启用的SASL机制",
+            mechanism.as_str(),
+            non_empty(&connect.username).unwrap_or_else(unset),
+            reason
+        ),
+        Err(msg) => msg,
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod security_test {
+    use crate::entity::db_entity::cluster;
+    use crate::utils::security::{describe_auth_failure, security_properties, validate_security};
+
+    fn connect() -> cluster::Model {
+        cluster::Model {
+            id: "local".to_string(),
+            servers: "localhost:9092".to_string(),
+            cluster_name: "local".to_string(),
+            username: None,
+            password: None,
+            timeout: 5000,
+            ssl: false,
+            sasl: None,
+            connected: false,
+            registry_url: None,
+            registry_username: None,
+            registry_password: None,
+            security_protocol: None,
+            ssl_ca_location: None,
+            ssl_certificate_location: None,
+            ssl_key_location: None,
+            ssl_key_password: None,
+            ssl_verify_hostname: true,
+            kerberos_keytab: None,
+            kerberos_principal: None,
+            kerberos_service_name: None,
+            oauth_token_endpoint: None,
+            oauth_client_id: None,
+            oauth_client_secret: None,
+            oauth_scope: None,
+        }
+    }
+
+    fn property<'a>(properties: &'a [(&'static str, String)], key: &str) -> Option<&'a str> {
+        properties
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn legacy_protocol_test() {
+        let mut model = connect();
+        let properties = security_properties(&model).unwrap();
+        assert_eq!(
+            property(&properties, "security.protocol"),
+            Some("PLAINTEXT")
+        );
+
+        model.ssl = true;
+        model.sasl = Some("SCRAM-SHA-512".to_string());
+        model.username = Some("admin".to_string());
+        let properties = security_properties(&model).unwrap();
+        assert_eq!(property(&properties, "security.protocol"), Some("SASL_SSL"));
+        assert_eq!(
+            property(&properties, "sasl.mechanisms"),
+            Some("SCRAM-SHA-512")
+        );
+        assert_eq!(property(&properties, "sasl.username"), Some("admin"));
+    }
+
+    #[test]
+    fn ssl_properties_test() {
+        let mut model = connect();
+        model.security_protocol = Some("ssl".to_string());
+        model.ssl_ca_location = Some("/etc/kafka/ca.pem".to_string());
+        model.ssl_verify_hostname = false;
+        // 非 SASL 协议不设置用户名
+        model.username = Some("admin".to_string());
+        let properties = security_properties(&model).unwrap();
+        assert_eq!(property(&properties, "security.protocol"), Some("SSL"));
+        assert_eq!(
+            property(&properties, "ssl.ca.location"),
+            Some("/etc/kafka/ca.pem")
+        );
+        assert_eq!(
+            property(&properties, "ssl.endpoint.identification.algorithm"),
+            Some("none")
+        );
+        assert_eq!(property(&properties, "sasl.username"), None);
+    }
+
+    #[test]
+    fn gssapi_properties_test() {
+        let mut model = connect();
+        model.security_protocol = Some("SASL_PLAINTEXT".to_string());
+        model.sasl = Some("GSSAPI".to_string());
+        model.kerberos_principal = Some("kafka-client@EXAMPLE.COM".to_string());
+        let properties = security_properties(&model).unwrap();
+        assert_eq!(property(&properties, "sasl.mechanisms"), Some("GSSAPI"));
+        assert_eq!(
+            property(&properties, "sasl.kerberos.service.name"),
+            Some("kafka")
+        );
+        assert_eq!(
+            property(&properties, "sasl.kerberos.principal"),
+            Some("kafka-client@EXAMPLE.COM")
+        );
+        assert_eq!(property(&properties, "sasl.username"), None);
+
+        model.kerberos_keytab = Some("/not/exists/client.keytab".to_string());
+        assert!(validate_security(&model).is_err());
+        let msg = describe_auth_failure(&model, "Failed to acquire credentials");
+        assert!(msg.contains("kafka-client@EXAMPLE.COM"));
+        assert!(msg.contains("/not/exists/client.keytab"));
+    }
+
+    #[test]
+    fn oauth_properties_test() {
+        let mut model = connect();
+        model.security_protocol = Some("SASL_SSL".to_string());
+        model.sasl = Some("OAUTHBEARER".to_string());
+        model.oauth_token_endpoint = Some("https://idp.example.com/token".to_string());
+        model.oauth_client_id = Some("easy-kafka".to_string());
+        assert!(security_properties(&model).is_err());
+
+        model.oauth_client_secret = Some("secret".to_string());
+        model.oauth_scope = Some("kafka".to_string());
+        let properties = security_properties(&model).unwrap();
+        assert_eq!(
+            property(&properties, "sasl.oauthbearer.method"),
+            Some("oidc")
+        );
+        assert_eq!(
+            property(&properties, "sasl.oauthbearer.token.endpoint.url"),
+            Some("https://idp.example.com/token")
+        );
+        assert_eq!(
+            property(&properties, "sasl.oauthbearer.scope"),
+            Some("kafka")
+        );
+        let msg = describe_auth_failure(&model, "Failed to retrieve OIDC token");
+        assert!(msg.starts_with("OAuth认证失败"));
+    }
+
+    #[test]
+    fn validate_test() {
+        let mut model = connect();
+        model.security_protocol = Some("SASL_PLAINTEXT".to_string());
+        assert!(validate_security(&model).is_err());
+        model.username = Some("admin".to_string());
+        assert!(validate_security(&model).is_ok());
+
+        model.security_protocol = Some("SASL_TLS".to_string());
+        assert!(validate_security(&model).is_err());
+
+        model.security_protocol = Some("SSL".to_string());
+        model.ssl_certificate_location = Some("/not/exists/client.pem".to_string());
+        assert!(validate_security(&model).is_err());
+        model.ssl_key_location = Some("/not/exists/client.key".to_string());
+        assert!(validate_security(&model).is_err());
+    }
+}